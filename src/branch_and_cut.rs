@@ -0,0 +1,300 @@
+use crate::constraints::{ConstraintError, EdgeConstraints};
+use crate::graphs::Graph;
+use crate::ilp::*;
+use crate::mps_alg::*;
+use crate::planarity::IncrementalPlanarSubgraph;
+use crate::weights::EdgeWeights;
+use good_lp::*;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// The number of separation passes over a single solution, each removing different edges of the found subgraphs.
+const SEPARATION_PASSES: usize = 3;
+
+/// Sums the variables associated with the given edges.
+///
+/// # Arguments
+/// * `edges` - A slice of edges represented as tuples of vertex indices.
+/// * `s` - A hashmap mapping edges to their corresponding variables.
+///
+/// # Returns
+/// * An expression representing the sum of the variables associated with the edges.
+fn sum_s_over_e(edges: &[(usize, usize)], s: &HashMap<(usize, usize), Variable>) -> Expression {
+    edges.iter().fold(Expression::from(0), |acc, e| acc + s[e])
+}
+
+/// Finds Kuratowski subgraphs violated by the given solution and turns it into a planar subgraph.
+///
+/// In every pass, after a subgraph is found one of its edges that is not mandatory is removed, so that the next one
+/// differs from the previous. Different passes remove different edges. The heaviest planar graph left by a pass is
+/// then extended greedily with the allowed edges of the input graph, the mandatory ones first and then from the
+/// heaviest, as long as planarity is preserved. The planarity of each insertion is checked incrementally against an
+/// embedding of the current subgraph.
+///
+/// # Arguments
+/// * `g` - A reference to the input graph.
+/// * `weights` - A reference to the weights of the edges.
+/// * `constraints` - A reference to the mandatory and forbidden edges, the mandatory ones being planar.
+/// * `solution` - A reference to the graph selected by the current solution.
+///
+/// # Returns
+/// * A tuple of the Kuratowski subgraphs, each given as a vector of edges, and a planar subgraph of the input graph.
+fn separate_kuratowski_subgraphs(
+    g: &Graph,
+    weights: &EdgeWeights,
+    constraints: &EdgeConstraints,
+    solution: &Graph,
+) -> (Vec<Vec<(usize, usize)>>, Graph) {
+    let mut cuts: Vec<Vec<(usize, usize)>> = Vec::new();
+    let mut planar = Graph::empty(g.num_of_vertices());
+
+    for pass in 0..SEPARATION_PASSES {
+        let mut h = solution.clone();
+
+        while let Some(mut k) = h.kuratowski_subgraph() {
+            k.sort_unstable();
+            let removable = k
+                .iter()
+                .filter(|&&(u, v)| !constraints.is_mandatory(u, v))
+                .collect::<Vec<_>>();
+            let (u, v) = *removable[pass * removable.len() / SEPARATION_PASSES];
+            h.remove_edge(u, v);
+            if !cuts.contains(&k) {
+                cuts.push(k);
+            }
+        }
+
+        if weights.total_weight(&h) > weights.total_weight(&planar) {
+            planar = h;
+        }
+    }
+
+    let mut edges = g.all_edges();
+    edges.sort_by(|&(a, b), &(c, d)| {
+        let by_constraint = constraints
+            .is_mandatory(c, d)
+            .cmp(&constraints.is_mandatory(a, b));
+        by_constraint.then(weights.weight(c, d).total_cmp(&weights.weight(a, b)))
+    });
+
+    let mut subgraph =
+        IncrementalPlanarSubgraph::new(&planar).expect("no Kuratowski subgraph is left");
+    for (u, v) in edges {
+        if !constraints.is_forbidden(u, v) {
+            subgraph.try_add_edge(u, v);
+        }
+    }
+
+    (cuts, subgraph.into_graph())
+}
+
+/// Computes the maximum planar subgraph using a branch-and-cut approach with Kuratowski constraints.
+///
+/// # Arguments
+/// * `g` - A reference to the input graph.
+///
+/// # Returns
+/// * The maximum planar subgraph of the input graph.
+pub fn branch_and_cut_mps(g: &Graph) -> Graph {
+    branch_and_cut_mps_with_time_limit(g, None).mps
+}
+
+/// Computes the maximum planar subgraph using a branch-and-cut approach, stopping after the time limit.
+///
+/// The initial integer program only contains the Euler bound. Whenever the optimal solution is not planar,
+/// constraints of the form `sum of s_e over K <= |K| - 1` are added for Kuratowski subgraphs `K` of the solution
+/// and the program is solved again. The planar subgraphs obtained during separation are kept as incumbents and the
+/// search stops as soon as one of them matches the upper bound.
+///
+/// # Arguments
+/// * `g` - A reference to the input graph.
+/// * `time_limit` - The optional wall-clock limit shared by all solved programs.
+///
+/// # Returns
+/// * The best planar subgraph found together with the proven upper bound and the gap.
+pub fn branch_and_cut_mps_with_time_limit(g: &Graph, time_limit: Option<Duration>) -> IlpSolution {
+    let options = IlpOptions {
+        time_limit,
+        ..Default::default()
+    };
+    branch_and_cut_mps_with_options(g, &options)
+        .expect("a program without fixed edges is always feasible")
+}
+
+/// Computes a maximum weight planar subgraph using a branch-and-cut approach.
+///
+/// The initial solution, if given, is the first incumbent. Otherwise, or if it is not a planar subgraph of `g`
+/// satisfying the constraints, the search starts from the mandatory edges.
+///
+/// # Arguments
+/// * `g` - A reference to the input graph.
+/// * `options` - A reference to the weights of the edges, the fixed edges, the initial solution and the time limit.
+///
+/// # Returns
+/// * `Ok(IlpSolution)` containing the best planar subgraph found, never lighter than the initial one, together with
+///   the proven upper bound and the gap, or the `ConstraintError` if no planar subgraph satisfies the constraints.
+pub fn branch_and_cut_mps_with_options(
+    g: &Graph,
+    options: &IlpOptions,
+) -> Result<IlpSolution, ConstraintError> {
+    let mandatory = options.constraints.validate(g)?;
+    let weights = &options.weights;
+    let constraints = &options.constraints;
+    let deadline = options.time_limit.map(|limit| Instant::now() + limit);
+    let n = g.num_of_vertices();
+    let m = g.num_of_edges();
+    let edges = g.all_edges();
+    let mut cuts: Vec<Vec<(usize, usize)>> = Vec::new();
+    let mut best = options.valid_initial(g).cloned().unwrap_or(mandatory);
+    let mut upper_bound = f64::INFINITY;
+
+    loop {
+        let remaining = deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()));
+        if remaining == Some(Duration::ZERO) {
+            return Ok(IlpSolution::new(
+                g,
+                weights,
+                constraints,
+                best,
+                upper_bound,
+                true,
+            ));
+        }
+
+        let mut vars = ProblemVariables::new();
+        let mut s = HashMap::with_capacity(m);
+
+        // s_e = 1 iff edge e is in the mps
+        for &e in edges.iter() {
+            let s_e = vars.add(variable().binary());
+            s.insert(e, s_e);
+        }
+
+        let objective = weighted_sum(&edges, &s, weights);
+        let columns = column_indices(&vars);
+        let mut problem = vars.maximise(objective).using(highs);
+
+        // Euler criterion
+        if n > 2 {
+            let edges_sum = sum_s_over_e(&edges, &s);
+            let bound = Expression::from((3 * n - 6) as i32);
+            problem = problem.with(constraint!(edges_sum <= bound));
+        }
+
+        problem = fix_edges(problem, &s, constraints);
+
+        // Kuratowski constraints
+        for k in cuts.iter() {
+            let k_sum = sum_s_over_e(k, &s);
+            problem = problem.with(constraint!(k_sum <= (k.len() - 1) as i32));
+        }
+
+        // the best planar subgraph satisfies all Kuratowski constraints
+        let mut initial_solution = HashMap::new();
+        select_edges(&mut initial_solution, &s, &best);
+
+        let SolverOutcome {
+            mps,
+            dual_bound,
+            time_limited,
+            infeasible,
+            ..
+        } = solve_with_time_limit(
+            g,
+            weights,
+            problem,
+            &columns,
+            &s,
+            &initial_solution,
+            remaining,
+        );
+        if infeasible {
+            return Ok(IlpSolution::new(
+                g,
+                weights,
+                constraints,
+                best,
+                upper_bound,
+                false,
+            ));
+        }
+        upper_bound = upper_bound.min(dual_bound);
+
+        let (new_cuts, planar) = separate_kuratowski_subgraphs(g, weights, constraints, &mps);
+        if new_cuts.is_empty() && !time_limited {
+            return Ok(IlpSolution::new(
+                g,
+                weights,
+                constraints,
+                mps,
+                upper_bound,
+                false,
+            ));
+        }
+
+        if weights.total_weight(&planar) > weights.total_weight(&best) {
+            best = planar;
+        }
+
+        // the solution is an upper bound, so a planar subgraph of the same weight is optimal
+        let solution = IlpSolution::new(
+            g,
+            weights,
+            constraints,
+            best.clone(),
+            upper_bound,
+            time_limited,
+        );
+        if solution.is_optimal() || time_limited {
+            return Ok(solution);
+        }
+
+        cuts.extend(new_cuts);
+    }
+}
+
+/// A struct representing the branch-and-cut MPS algorithm.
+#[derive(Default)]
+pub struct BranchAndCutMps {
+    /// The optional wall-clock limit for the whole search.
+    pub time_limit: Option<Duration>,
+}
+
+impl MpsAlgorithm for BranchAndCutMps {
+    /// Computes a maximum weight planar subgraph satisfying the fixed-edge constraints using the branch-and-cut
+    /// algorithm.
+    ///
+    /// # Arguments
+    /// * `g` - A reference to the input graph.
+    /// * `weights` - A reference to the weights of the edges.
+    /// * `constraints` - A reference to the mandatory and forbidden edges.
+    ///
+    /// # Returns
+    /// * `Ok(MpsResult)` containing the subgraph, its status and the upper bound, or the `ConstraintError` if no
+    ///   planar subgraph satisfies the constraints.
+    fn solve_constrained(
+        &self,
+        g: &Graph,
+        weights: &EdgeWeights,
+        constraints: &EdgeConstraints,
+    ) -> Result<MpsResult, ConstraintError> {
+        let start = Instant::now();
+        let options = IlpOptions {
+            weights: weights.clone(),
+            constraints: constraints.clone(),
+            time_limit: self.time_limit,
+            ..Default::default()
+        };
+        let solution = branch_and_cut_mps_with_options(g, &options)?;
+
+        Ok(MpsResult::exact(g, solution, start.elapsed()))
+    }
+
+    /// Returns the name of the algorithm.
+    ///
+    /// # Returns
+    /// * A string slice representing the name of the algorithm.
+    fn name(&self) -> &'static str {
+        "BranchAndCut"
+    }
+}
@@ -0,0 +1,146 @@
+use crate::graphs::Graph;
+use std::collections::HashSet;
+use std::fmt;
+
+/// An enum representing the reasons why fixed-edge constraints cannot be satisfied.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConstraintError {
+    /// A mandatory edge is not an edge of the input graph.
+    MissingEdge(usize, usize),
+    /// An edge is both mandatory and forbidden.
+    Conflict(usize, usize),
+    /// The mandatory edges contain the given Kuratowski subgraph, so no planar subgraph contains all of them.
+    NonPlanarMandatory(Vec<(usize, usize)>),
+}
+
+impl fmt::Display for ConstraintError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConstraintError::MissingEdge(u, v) => {
+                write!(f, "mandatory edge ({u}, {v}) is not an edge of the graph")
+            }
+            ConstraintError::Conflict(u, v) => {
+                write!(f, "edge ({u}, {v}) is both mandatory and forbidden")
+            }
+            ConstraintError::NonPlanarMandatory(k) => write!(
+                f,
+                "the mandatory edges are not planar, they contain the Kuratowski subgraph {k:?}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ConstraintError {}
+
+/// A struct representing the edges that have to be kept in and the edges that have to be left out of a planar
+/// subgraph.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EdgeConstraints {
+    mandatory: HashSet<(usize, usize)>,
+    forbidden: HashSet<(usize, usize)>,
+}
+
+impl EdgeConstraints {
+    /// Creates constraints that neither force nor forbid any edge.
+    ///
+    /// # Returns
+    /// * A new instance of `EdgeConstraints`.
+    pub fn new() -> Self {
+        EdgeConstraints {
+            mandatory: HashSet::new(),
+            forbidden: HashSet::new(),
+        }
+    }
+
+    /// Forces an edge into the planar subgraph.
+    ///
+    /// # Arguments
+    /// * `u` - The index of the first vertex.
+    /// * `v` - The index of the second vertex.
+    pub fn require(&mut self, u: usize, v: usize) {
+        self.mandatory.insert((u.min(v), u.max(v)));
+    }
+
+    /// Forbids an edge from the planar subgraph.
+    ///
+    /// # Arguments
+    /// * `u` - The index of the first vertex.
+    /// * `v` - The index of the second vertex.
+    pub fn forbid(&mut self, u: usize, v: usize) {
+        self.forbidden.insert((u.min(v), u.max(v)));
+    }
+
+    /// Checks if an edge is mandatory.
+    ///
+    /// # Arguments
+    /// * `u` - The index of the first vertex.
+    /// * `v` - The index of the second vertex.
+    ///
+    /// # Returns
+    /// * `true` if the edge has to be kept, `false` otherwise.
+    pub fn is_mandatory(&self, u: usize, v: usize) -> bool {
+        self.mandatory.contains(&(u.min(v), u.max(v)))
+    }
+
+    /// Checks if an edge is forbidden.
+    ///
+    /// # Arguments
+    /// * `u` - The index of the first vertex.
+    /// * `v` - The index of the second vertex.
+    ///
+    /// # Returns
+    /// * `true` if the edge has to be left out, `false` otherwise.
+    pub fn is_forbidden(&self, u: usize, v: usize) -> bool {
+        self.forbidden.contains(&(u.min(v), u.max(v)))
+    }
+
+    /// Returns the mandatory edges.
+    ///
+    /// # Returns
+    /// * A sorted vector of the mandatory edges with the smaller vertex index first.
+    pub fn mandatory_edges(&self) -> Vec<(usize, usize)> {
+        let mut edges = self.mandatory.iter().copied().collect::<Vec<_>>();
+        edges.sort_unstable();
+        edges
+    }
+
+    /// Returns the forbidden edges.
+    ///
+    /// # Returns
+    /// * A sorted vector of the forbidden edges with the smaller vertex index first.
+    pub fn forbidden_edges(&self) -> Vec<(usize, usize)> {
+        let mut edges = self.forbidden.iter().copied().collect::<Vec<_>>();
+        edges.sort_unstable();
+        edges
+    }
+
+    /// Checks that the constraints can be satisfied by a planar subgraph of a graph.
+    ///
+    /// Forbidden edges missing from the graph are ignored.
+    ///
+    /// # Arguments
+    /// * `g` - A reference to the input graph.
+    ///
+    /// # Returns
+    /// * `Ok(Graph)` containing the subgraph of the mandatory edges, or the `ConstraintError` describing the first
+    ///   violated requirement.
+    pub fn validate(&self, g: &Graph) -> Result<Graph, ConstraintError> {
+        let n = g.num_of_vertices();
+        let mut mandatory = Graph::empty(n);
+
+        for (u, v) in self.mandatory_edges() {
+            if v >= n || !g.has_edge(u, v) {
+                return Err(ConstraintError::MissingEdge(u, v));
+            }
+            if self.is_forbidden(u, v) {
+                return Err(ConstraintError::Conflict(u, v));
+            }
+            mandatory.add_edge(u, v);
+        }
+
+        match mandatory.kuratowski_subgraph() {
+            Some(k) => Err(ConstraintError::NonPlanarMandatory(k)),
+            None => Ok(mandatory),
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use crate::graphs::{Graph, GraphError};
+use std::collections::HashMap;
+
+/// The number of colours of the Graphviz `set312` colour scheme used for components.
+const NUM_OF_COLORS: usize = 12;
+
+impl Graph {
+    /// Formats the graph in the Graphviz DOT language.
+    ///
+    /// Edges of the planar subgraph are drawn solid and the removed edges dashed and red. If components are given,
+    /// e.g. the ones merged by a match and merge algorithm, the vertices of every component with more than one
+    /// vertex are filled with the same colour. The colours repeat after twelve components.
+    ///
+    /// # Arguments
+    /// * `mps` - An optional planar subgraph, all edges are drawn solid without it.
+    /// * `components` - An optional slice with the index of the component of every vertex.
+    ///
+    /// # Returns
+    /// * The DOT document.
+    pub fn to_dot(&self, mps: Option<&Graph>, components: Option<&[usize]>) -> String {
+        let mut text = String::from("graph G {\n");
+        text.push_str(
+            "  node [shape=circle, style=filled, fillcolor=white, colorscheme=set312];\n",
+        );
+
+        let mut sizes = HashMap::new();
+        for &component in components.unwrap_or_default() {
+            *sizes.entry(component).or_insert(0) += 1;
+        }
+
+        for v in 0..self.num_of_vertices() {
+            match components.and_then(|components| components.get(v)) {
+                Some(component) if sizes[component] > 1 => {
+                    let color = component % NUM_OF_COLORS + 1;
+                    text.push_str(&format!("  {v} [fillcolor={color}];\n"));
+                }
+                _ => text.push_str(&format!("  {v};\n")),
+            }
+        }
+
+        for (u, v) in self.all_edges() {
+            if mps.is_some_and(|h| !h.has_edge(u, v)) {
+                text.push_str(&format!("  {u} -- {v} [style=dashed, color=red];\n"));
+            } else {
+                text.push_str(&format!("  {u} -- {v};\n"));
+            }
+        }
+
+        text.push_str("}\n");
+        text
+    }
+
+    /// Writes the graph to a DOT file.
+    ///
+    /// # Arguments
+    /// * `filename` - The name of the file to write the graph to.
+    /// * `mps` - An optional planar subgraph, all edges are drawn solid without it.
+    /// * `components` - An optional slice with the index of the component of every vertex.
+    ///
+    /// # Returns
+    /// * `Ok(())` if the file was written, or the `GraphError` describing why it could not be.
+    pub fn write_dot(
+        &self,
+        filename: &str,
+        mps: Option<&Graph>,
+        components: Option<&[usize]>,
+    ) -> Result<(), GraphError> {
+        Ok(std::fs::write(filename, self.to_dot(mps, components))?)
+    }
+}
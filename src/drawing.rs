@@ -0,0 +1,521 @@
+use crate::graphs::{Graph, GraphError};
+use crate::planarity::triangulate;
+use crate::schnyder_wood::schnyder_wood;
+
+/// The distance in pixels between neighboring grid points in SVG files.
+const SVG_SCALE: f64 = 40.0;
+
+/// The margin in pixels around the drawing in SVG files.
+const SVG_MARGIN: f64 = 20.0;
+
+/// The radius in pixels of the vertices in SVG files.
+const SVG_RADIUS: f64 = 8.0;
+
+/// The distance of the control point of a curved edge from the middle of the segment, relative to its length.
+const CURVE_BEND: f64 = 0.25;
+
+/// The relative residual at which the conjugate gradient method computing a Tutte embedding stops.
+const TUTTE_TOLERANCE: f64 = 1e-14;
+
+/// An enum representing the straight-line layouts of planar graphs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Layout {
+    /// The Tutte barycentric embedding of a triangulation of the graph, with every inner vertex at the average
+    /// position of its neighbors.
+    Tutte,
+    /// The grid drawing given by the coordinates of a Schnyder wood of a triangulation of the graph.
+    Schnyder,
+}
+
+/// An enum representing the labels of the vertices in TikZ pictures.
+#[derive(Clone, Debug, PartialEq)]
+pub enum VertexLabels {
+    /// The vertices are drawn without labels.
+    Empty,
+    /// Every vertex is labelled by its index.
+    Indices,
+    /// Every vertex is labelled by the given LaTeX code, vertices without a label are left empty.
+    Custom(Vec<String>),
+}
+
+/// The options of TikZ pictures.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TikzOptions {
+    /// The labels of the vertices, their indices by default.
+    pub labels: VertexLabels,
+    /// The length in centimeters of a unit of the drawing, 0.5 by default.
+    pub scale: f64,
+    /// The TikZ style of the vertices.
+    pub vertex_style: String,
+    /// The TikZ style of the edges of the drawn graph, e.g. the kept edges of an MPS.
+    pub kept_style: String,
+    /// The TikZ style of the removed edges, which are also bent.
+    pub removed_style: String,
+}
+
+impl Default for TikzOptions {
+    /// Creates the default options, with small circles labelled by indices, black kept edges and dashed red removed
+    /// edges.
+    ///
+    /// # Returns
+    /// * A new instance of `TikzOptions`.
+    fn default() -> Self {
+        TikzOptions {
+            labels: VertexLabels::Indices,
+            scale: 0.5,
+            vertex_style:
+                "circle, draw, fill=white, inner sep=1pt, minimum size=12pt, font=\\scriptsize"
+                    .to_string(),
+            kept_style: "thick".to_string(),
+            removed_style: "red, dashed, bend left=20".to_string(),
+        }
+    }
+}
+
+/// A struct representing a straight-line drawing of a graph, given by the positions of its vertices.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StraightLineDrawing {
+    /// The position of every vertex, the y axis pointing up.
+    pub positions: Vec<(f64, f64)>,
+}
+
+/// Computes the orientation of three points.
+///
+/// # Arguments
+/// * `a` - The first point.
+/// * `b` - The second point.
+/// * `c` - The third point.
+///
+/// # Returns
+/// * A positive number if the points turn counterclockwise, a negative one if clockwise and zero if they are
+///   collinear.
+fn orientation(a: (f64, f64), b: (f64, f64), c: (f64, f64)) -> f64 {
+    (b.0 - a.0) * (c.1 - a.1) - (b.1 - a.1) * (c.0 - a.0)
+}
+
+/// Solves the equations placing every free vertex at the average position of its neighbors in one coordinate.
+///
+/// The system is the Laplacian of the graph restricted to the free vertices, which is symmetric and positive
+/// definite if every component of the graph contains a fixed vertex, so the conjugate gradient method applies.
+///
+/// # Arguments
+/// * `g` - A reference to the graph.
+/// * `fixed` - A slice telling if each vertex is fixed.
+/// * `coordinate` - A mutable slice with the coordinate of every vertex, read for the fixed ones and written for
+///   the free ones.
+fn solve_barycentric(g: &Graph, fixed: &[bool], coordinate: &mut [f64]) {
+    let n = coordinate.len();
+    let laplacian = |x: &[f64]| {
+        (0..n)
+            .map(|v| {
+                if fixed[v] {
+                    return 0.0;
+                }
+                let neighbors = g.neighbors(v).unwrap();
+                let free_sum = neighbors
+                    .iter()
+                    .filter(|&&u| !fixed[u])
+                    .map(|&u| x[u])
+                    .sum::<f64>();
+                neighbors.len() as f64 * x[v] - free_sum
+            })
+            .collect::<Vec<f64>>()
+    };
+    let dot = |a: &[f64], b: &[f64]| a.iter().zip(b).map(|(x, y)| x * y).sum::<f64>();
+
+    let b = (0..n)
+        .map(|v| {
+            if fixed[v] {
+                return 0.0;
+            }
+            let neighbors = g.neighbors(v).unwrap();
+            neighbors
+                .iter()
+                .filter(|&&u| fixed[u])
+                .map(|&u| coordinate[u])
+                .sum::<f64>()
+        })
+        .collect::<Vec<f64>>();
+
+    let mut x = vec![0.0; n];
+    let mut r = b.clone();
+    let mut d = r.clone();
+    let mut rr = dot(&r, &r);
+    let threshold = TUTTE_TOLERANCE * TUTTE_TOLERANCE * rr.max(f64::MIN_POSITIVE);
+
+    // in exact arithmetic the method ends after at most n steps, more absorb the rounding errors
+    for _ in 0..10 * n + 10 {
+        if rr <= threshold {
+            break;
+        }
+        let ld = laplacian(&d);
+        let alpha = rr / dot(&d, &ld);
+        for v in 0..n {
+            x[v] += alpha * d[v];
+            r[v] -= alpha * ld[v];
+        }
+        let next = dot(&r, &r);
+        for v in 0..n {
+            d[v] = r[v] + next / rr * d[v];
+        }
+        rr = next;
+    }
+
+    for v in (0..n).filter(|&v| !fixed[v]) {
+        coordinate[v] = x[v];
+    }
+}
+
+/// Rounds a coordinate for text output.
+///
+/// # Arguments
+/// * `x` - The coordinate.
+///
+/// # Returns
+/// * The coordinate rounded to four decimal places, without trailing zeros.
+fn round(x: f64) -> f64 {
+    (x * 10000.0).round() / 10000.0 + 0.0
+}
+
+/// Checks if a point lies on a segment.
+///
+/// # Arguments
+/// * `p` - The point.
+/// * `a` - The first end of the segment.
+/// * `b` - The second end of the segment.
+///
+/// # Returns
+/// * `true` if the point lies on the closed segment, `false` otherwise.
+fn on_segment(p: (f64, f64), a: (f64, f64), b: (f64, f64)) -> bool {
+    orientation(a, b, p) == 0.0
+        && p.0 >= a.0.min(b.0)
+        && p.0 <= a.0.max(b.0)
+        && p.1 >= a.1.min(b.1)
+        && p.1 <= a.1.max(b.1)
+}
+
+impl StraightLineDrawing {
+    /// Computes a plane straight-line drawing of a planar graph.
+    ///
+    /// # Arguments
+    /// * `g` - A reference to the planar graph, e.g. the subgraph found by an MPS algorithm.
+    /// * `layout` - The layout to use.
+    ///
+    /// # Returns
+    /// * `Some(StraightLineDrawing)` if the graph is planar, `None` otherwise.
+    pub fn new(g: &Graph, layout: Layout) -> Option<Self> {
+        match layout {
+            Layout::Tutte => StraightLineDrawing::tutte(g),
+            Layout::Schnyder => StraightLineDrawing::schnyder(g),
+        }
+    }
+
+    /// Computes the Tutte barycentric embedding of a triangulation of a planar graph.
+    ///
+    /// The outer face is drawn as a regular polygon whose side is `2n - 5`, the size of the Schnyder grid, and the
+    /// positions of the inner vertices are found by the conjugate gradient method. The triangulation is 3-connected, so
+    /// the drawing is plane by the theorem of Tutte. Its vertices may however get exponentially close to each other,
+    /// so with a few hundred vertices rounding errors can make tiny faces overlap; the Schnyder layout is exact.
+    ///
+    /// # Arguments
+    /// * `g` - A reference to the planar graph.
+    ///
+    /// # Returns
+    /// * `Some(StraightLineDrawing)` if the graph is planar, `None` otherwise.
+    pub fn tutte(g: &Graph) -> Option<Self> {
+        let n = g.num_of_vertices();
+        let (h, embedding) = triangulate(g)?;
+        if n < 3 {
+            return Some(StraightLineDrawing::on_line(n));
+        }
+
+        let outer = &embedding.faces()[0];
+        let k = outer.len();
+        let side = (2 * n - 5) as f64;
+        let radius = side / (2.0 * (std::f64::consts::PI / k as f64).sin());
+        let mut positions = vec![(0.0, 0.0); n];
+        let mut fixed = vec![false; n];
+
+        // the corners of the polygon follow the outer face
+        for (j, &v) in outer.iter().enumerate() {
+            let angle =
+                std::f64::consts::FRAC_PI_2 - 2.0 * std::f64::consts::PI * j as f64 / k as f64;
+            positions[v] = (radius * (1.0 + angle.cos()), radius * (1.0 + angle.sin()));
+            fixed[v] = true;
+        }
+
+        let mut xs = positions.iter().map(|p| p.0).collect::<Vec<f64>>();
+        let mut ys = positions.iter().map(|p| p.1).collect::<Vec<f64>>();
+        solve_barycentric(&h, &fixed, &mut xs);
+        solve_barycentric(&h, &fixed, &mut ys);
+        let positions = xs.into_iter().zip(ys).collect();
+
+        Some(StraightLineDrawing { positions })
+    }
+
+    /// Computes the grid drawing given by a Schnyder wood of a triangulation of a planar graph.
+    ///
+    /// Every vertex is placed at its first two Schnyder coordinates, which gives a plane drawing on the
+    /// `(2n - 5) x (2n - 5)` grid.
+    ///
+    /// # Arguments
+    /// * `g` - A reference to the planar graph.
+    ///
+    /// # Returns
+    /// * `Some(StraightLineDrawing)` if the graph is planar, `None` otherwise.
+    pub fn schnyder(g: &Graph) -> Option<Self> {
+        let n = g.num_of_vertices();
+        if n < 3 {
+            return g.is_planar().then(|| StraightLineDrawing::on_line(n));
+        }
+
+        let coordinates = schnyder_wood(g)?
+            .coordinates()
+            .iter()
+            .map(|c| (c[0], c[1]))
+            .collect::<Vec<_>>();
+        Some(StraightLineDrawing::from_grid(&coordinates))
+    }
+
+    /// Places the vertices on a horizontal line, which is a plane drawing of every graph with at most two vertices.
+    ///
+    /// # Arguments
+    /// * `n` - The number of vertices.
+    ///
+    /// # Returns
+    /// * A new `StraightLineDrawing`.
+    fn on_line(n: usize) -> Self {
+        StraightLineDrawing {
+            positions: (0..n).map(|v| (v as f64, 0.0)).collect(),
+        }
+    }
+
+    /// Creates a drawing from integer grid coordinates.
+    ///
+    /// # Arguments
+    /// * `coordinates` - A slice with the grid point of every vertex.
+    ///
+    /// # Returns
+    /// * A new `StraightLineDrawing`.
+    pub fn from_grid(coordinates: &[(usize, usize)]) -> Self {
+        StraightLineDrawing {
+            positions: coordinates
+                .iter()
+                .map(|&(x, y)| (x as f64, y as f64))
+                .collect(),
+        }
+    }
+
+    /// Checks if the drawing of a graph is a plane drawing.
+    ///
+    /// The positions are compared exactly, so the check is reliable for grid drawings.
+    ///
+    /// # Arguments
+    /// * `g` - A reference to the drawn graph.
+    ///
+    /// # Returns
+    /// * `true` if the vertices are at distinct positions, no vertex lies on an edge it does not belong to and no
+    ///   two edges cross, `false` otherwise.
+    pub fn is_plane(&self, g: &Graph) -> bool {
+        let n = g.num_of_vertices();
+        let p = &self.positions;
+        if p.len() != n {
+            return false;
+        }
+
+        for u in 0..n {
+            for v in u + 1..n {
+                if p[u] == p[v] {
+                    return false;
+                }
+            }
+        }
+
+        let edges = g.all_edges();
+        for &(a, b) in edges.iter() {
+            if (0..n).any(|v| v != a && v != b && on_segment(p[v], p[a], p[b])) {
+                return false;
+            }
+        }
+
+        for (i, &(a, b)) in edges.iter().enumerate() {
+            for &(c, d) in edges[i + 1..].iter() {
+                if a == c || a == d || b == c || b == d {
+                    continue;
+                }
+
+                let crossing = orientation(p[a], p[b], p[c]) * orientation(p[a], p[b], p[d]) < 0.0
+                    && orientation(p[c], p[d], p[a]) * orientation(p[c], p[d], p[b]) < 0.0;
+                if crossing {
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+
+    /// Formats the drawing of a graph as SVG.
+    ///
+    /// A unit of the drawing becomes 40 pixels and the vertices are drawn as labelled circles. The removed edges,
+    /// e.g. the edges of the input graph missing from its planar subgraph, are drawn as dashed red curves, so they
+    /// do not run over each other.
+    ///
+    /// # Arguments
+    /// * `g` - A reference to the drawn graph.
+    /// * `removed_edges` - A slice of further edges to draw as curves, possibly empty.
+    ///
+    /// # Returns
+    /// * The SVG document.
+    pub fn to_svg(&self, g: &Graph, removed_edges: &[(usize, usize)]) -> String {
+        let max_x = self.positions.iter().map(|p| p.0).fold(0.0, f64::max);
+        let max_y = self.positions.iter().map(|p| p.1).fold(0.0, f64::max);
+        let min_x = self.positions.iter().map(|p| p.0).fold(max_x, f64::min);
+        let min_y = self.positions.iter().map(|p| p.1).fold(max_y, f64::min);
+        let width = (max_x - min_x) * SVG_SCALE + 2.0 * SVG_MARGIN;
+        let height = (max_y - min_y) * SVG_SCALE + 2.0 * SVG_MARGIN;
+
+        // the y axis of SVG points down
+        let point = |v: usize| {
+            let (x, y) = self.positions[v];
+            (
+                (x - min_x) * SVG_SCALE + SVG_MARGIN,
+                (max_y - y) * SVG_SCALE + SVG_MARGIN,
+            )
+        };
+
+        let mut text = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" viewBox=\"0 0 {width} {height}\">\n"
+        );
+
+        text.push_str("  <g stroke=\"black\" stroke-width=\"1.5\">\n");
+        for (u, v) in g.all_edges() {
+            let ((x1, y1), (x2, y2)) = (point(u), point(v));
+            text.push_str(&format!(
+                "    <line x1=\"{x1}\" y1=\"{y1}\" x2=\"{x2}\" y2=\"{y2}\"/>\n"
+            ));
+        }
+        text.push_str("  </g>\n");
+
+        if !removed_edges.is_empty() {
+            text.push_str(
+                "  <g stroke=\"red\" stroke-width=\"1\" stroke-dasharray=\"4 3\" fill=\"none\">\n",
+            );
+            for &(u, v) in removed_edges {
+                let ((x1, y1), (x2, y2)) = (point(u), point(v));
+                let (cx, cy) = (
+                    (x1 + x2) / 2.0 + CURVE_BEND * (y2 - y1),
+                    (y1 + y2) / 2.0 - CURVE_BEND * (x2 - x1),
+                );
+                text.push_str(&format!(
+                    "    <path d=\"M {x1} {y1} Q {cx} {cy} {x2} {y2}\"/>\n"
+                ));
+            }
+            text.push_str("  </g>\n");
+        }
+
+        text.push_str("  <g font-family=\"sans-serif\" font-size=\"9\" text-anchor=\"middle\">\n");
+        for v in 0..self.positions.len() {
+            let (x, y) = point(v);
+            text.push_str(&format!(
+                "    <circle cx=\"{x}\" cy=\"{y}\" r=\"{SVG_RADIUS}\" fill=\"white\" stroke=\"black\"/>\n"
+            ));
+            text.push_str(&format!(
+                "    <text x=\"{x}\" y=\"{}\">{v}</text>\n",
+                y + 3.0
+            ));
+        }
+        text.push_str("  </g>\n</svg>\n");
+
+        text
+    }
+
+    /// Writes the drawing of a graph to an SVG file.
+    ///
+    /// # Arguments
+    /// * `g` - A reference to the drawn graph.
+    /// * `removed_edges` - A slice of further edges to draw as curves, possibly empty.
+    /// * `filename` - The name of the file to write the drawing to.
+    ///
+    /// # Returns
+    /// * `Ok(())` if the file was written, or the `GraphError` describing why it could not be.
+    pub fn write_svg(
+        &self,
+        g: &Graph,
+        removed_edges: &[(usize, usize)],
+        filename: &str,
+    ) -> Result<(), GraphError> {
+        Ok(std::fs::write(filename, self.to_svg(g, removed_edges))?)
+    }
+
+    /// Formats the drawing of a graph as a TikZ picture.
+    ///
+    /// The vertices become nodes `v0`, `v1`, ... and the picture defines the styles `vertex`, `kept` and `removed`,
+    /// so they can be adjusted in the LaTeX document as well.
+    ///
+    /// # Arguments
+    /// * `g` - A reference to the drawn graph.
+    /// * `removed_edges` - A slice of further edges drawn in the removed style, possibly empty.
+    /// * `options` - A reference to the labels, the scale and the styles.
+    ///
+    /// # Returns
+    /// * The `tikzpicture` environment.
+    pub fn to_tikz(
+        &self,
+        g: &Graph,
+        removed_edges: &[(usize, usize)],
+        options: &TikzOptions,
+    ) -> String {
+        let mut text = format!("\\begin{{tikzpicture}}[scale={}]\n", options.scale);
+        text.push_str(&format!(
+            "  \\tikzset{{vertex/.style={{{}}}, kept/.style={{{}}}, removed/.style={{{}}}}}\n",
+            options.vertex_style, options.kept_style, options.removed_style
+        ));
+
+        for (v, &(x, y)) in self.positions.iter().enumerate() {
+            let label = match &options.labels {
+                VertexLabels::Empty => String::new(),
+                VertexLabels::Indices => v.to_string(),
+                VertexLabels::Custom(labels) => labels.get(v).cloned().unwrap_or_default(),
+            };
+            text.push_str(&format!(
+                "  \\node[vertex] (v{v}) at ({}, {}) {{{label}}};\n",
+                round(x),
+                round(y)
+            ));
+        }
+
+        for (u, v) in g.all_edges() {
+            text.push_str(&format!("  \\draw[kept] (v{u}) -- (v{v});\n"));
+        }
+        for &(u, v) in removed_edges {
+            text.push_str(&format!("  \\draw[removed] (v{u}) to (v{v});\n"));
+        }
+
+        text.push_str("\\end{tikzpicture}\n");
+        text
+    }
+
+    /// Writes the drawing of a graph to a file with a TikZ picture, ready to be included in a LaTeX document.
+    ///
+    /// # Arguments
+    /// * `g` - A reference to the drawn graph.
+    /// * `removed_edges` - A slice of further edges drawn in the removed style, possibly empty.
+    /// * `options` - A reference to the labels, the scale and the styles.
+    /// * `filename` - The name of the file to write the picture to.
+    ///
+    /// # Returns
+    /// * `Ok(())` if the file was written, or the `GraphError` describing why it could not be.
+    pub fn write_tikz(
+        &self,
+        g: &Graph,
+        removed_edges: &[(usize, usize)],
+        options: &TikzOptions,
+        filename: &str,
+    ) -> Result<(), GraphError> {
+        Ok(std::fs::write(
+            filename,
+            self.to_tikz(g, removed_edges, options),
+        )?)
+    }
+}
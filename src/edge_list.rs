@@ -0,0 +1,281 @@
+use crate::graphs::{Graph, GraphError, MAX_PARSED_VERTICES};
+
+/// An enum representing the index of the first vertex in a text format.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Indexing {
+    /// The vertices are numbered from 0.
+    ZeroBased,
+    /// The vertices are numbered from 1.
+    OneBased,
+}
+
+impl Indexing {
+    /// Returns the index of the first vertex.
+    ///
+    /// # Returns
+    /// * 0 or 1.
+    fn offset(self) -> usize {
+        match self {
+            Indexing::ZeroBased => 0,
+            Indexing::OneBased => 1,
+        }
+    }
+}
+
+/// Parses a vertex index.
+///
+/// # Arguments
+/// * `token` - The token to parse.
+/// * `indexing` - The index of the first vertex.
+/// * `line` - The number of the line, starting from 1, used in errors.
+///
+/// # Returns
+/// * `Ok(usize)` containing the zero-based index of the vertex, or `Err(GraphError::Parse)` if the token is not a
+///   valid index or exceeds the limit on the number of vertices.
+fn parse_vertex(token: &str, indexing: Indexing, line: usize) -> Result<usize, GraphError> {
+    token
+        .parse::<usize>()
+        .ok()
+        .and_then(|v| v.checked_sub(indexing.offset()))
+        .filter(|&v| v < MAX_PARSED_VERTICES)
+        .ok_or_else(|| GraphError::Parse {
+            line,
+            message: format!("`{token}` is not a valid vertex index"),
+        })
+}
+
+/// Adds an edge read from a file, ignoring repeated edges.
+///
+/// # Arguments
+/// * `graph` - A mutable reference to the graph being read.
+/// * `u` - The index of the first vertex.
+/// * `v` - The index of the second vertex.
+/// * `line` - The number of the line, starting from 1, used in errors.
+///
+/// # Returns
+/// * `Ok(())` if the edge was added or already present, `Err(GraphError::Parse)` if it is a self loop.
+pub(crate) fn add_parsed_edge(
+    graph: &mut Graph,
+    u: usize,
+    v: usize,
+    line: usize,
+) -> Result<(), GraphError> {
+    match graph.try_add_edge(u, v) {
+        Err(GraphError::SelfLoop(v)) => Err(GraphError::Parse {
+            line,
+            message: format!("self loop at vertex {v}"),
+        }),
+        _ => Ok(()),
+    }
+}
+
+impl Graph {
+    /// Parses a graph from a whitespace-separated edge list.
+    ///
+    /// Every line holds the two ends of an edge, any further tokens (e.g. weights) are ignored. Empty lines and
+    /// lines starting with `#` or `%` are comments. The number of vertices is one more than the largest index, so
+    /// isolated vertices after the last one used are lost. Repeated edges are read once. Graphs with more than
+    /// `2^24` vertices are rejected.
+    ///
+    /// # Arguments
+    /// * `text` - The contents of the edge list.
+    /// * `indexing` - The index of the first vertex.
+    ///
+    /// # Returns
+    /// * `Ok(Graph)` described by the edge list, or `Err(GraphError::Parse)` pointing to the first invalid line.
+    pub fn from_edge_list(text: &str, indexing: Indexing) -> Result<Graph, GraphError> {
+        let mut edges = Vec::new();
+
+        for (i, content) in text.lines().enumerate() {
+            let line = i + 1;
+            let content = content.trim();
+            if content.is_empty() || content.starts_with('#') || content.starts_with('%') {
+                continue;
+            }
+
+            let mut tokens = content.split_whitespace();
+            match (tokens.next(), tokens.next()) {
+                (Some(u), Some(v)) => {
+                    let u = parse_vertex(u, indexing, line)?;
+                    let v = parse_vertex(v, indexing, line)?;
+                    edges.push((u, v, line));
+                }
+                _ => {
+                    return Err(GraphError::Parse {
+                        line,
+                        message: "expected two vertex indices".to_string(),
+                    })
+                }
+            }
+        }
+
+        // the indices are below the limit, so the count cannot overflow
+        let n = edges
+            .iter()
+            .map(|&(u, v, _)| u.max(v) + 1)
+            .max()
+            .unwrap_or(0);
+        let mut graph = Graph::empty(n);
+        for (u, v, line) in edges {
+            add_parsed_edge(&mut graph, u, v, line)?;
+        }
+
+        Ok(graph)
+    }
+
+    /// Formats the graph as an edge list with one edge per line.
+    ///
+    /// # Arguments
+    /// * `indexing` - The index of the first vertex.
+    ///
+    /// # Returns
+    /// * A string with a comment stating the numbers of vertices and edges followed by the sorted edges.
+    pub fn to_edge_list(&self, indexing: Indexing) -> String {
+        let offset = indexing.offset();
+        let mut edges = self.all_edges();
+        edges.sort_unstable();
+
+        let mut text = format!(
+            "# {} vertices, {} edges\n",
+            self.num_of_vertices(),
+            self.num_of_edges()
+        );
+        for (u, v) in edges {
+            text.push_str(&format!("{} {}\n", u + offset, v + offset));
+        }
+        text
+    }
+
+    /// Reads a graph from an edge list file.
+    ///
+    /// # Arguments
+    /// * `filename` - The name of the file to read the graph from.
+    /// * `indexing` - The index of the first vertex.
+    ///
+    /// # Returns
+    /// * `Ok(Graph)` read from the file, or the `GraphError` describing why it could not be read.
+    pub fn read_edge_list(filename: &str, indexing: Indexing) -> Result<Graph, GraphError> {
+        Graph::from_edge_list(&std::fs::read_to_string(filename)?, indexing)
+    }
+
+    /// Writes the graph to an edge list file.
+    ///
+    /// # Arguments
+    /// * `filename` - The name of the file to write the graph to.
+    /// * `indexing` - The index of the first vertex.
+    ///
+    /// # Returns
+    /// * `Ok(())` if the file was written, `Err(GraphError::Io)` otherwise.
+    pub fn write_edge_list(&self, filename: &str, indexing: Indexing) -> Result<(), GraphError> {
+        Ok(std::fs::write(filename, self.to_edge_list(indexing))?)
+    }
+
+    /// Parses a graph in the DIMACS format.
+    ///
+    /// Lines starting with `c` are comments. The problem line `p edge n m` (or `p col n m`) declares the number of
+    /// vertices and has to precede the edge lines `e u v`, which use indices starting from 1. Repeated edges are
+    /// read once, so the declared number of edges is not enforced. At most `2^24` vertices may be declared.
+    ///
+    /// # Arguments
+    /// * `text` - The contents of the DIMACS file.
+    ///
+    /// # Returns
+    /// * `Ok(Graph)` described by the file, or `Err(GraphError::Parse)` pointing to the first invalid line.
+    pub fn from_dimacs(text: &str) -> Result<Graph, GraphError> {
+        let mut graph: Option<Graph> = None;
+
+        for (i, content) in text.lines().enumerate() {
+            let line = i + 1;
+            let tokens = content.split_whitespace().collect::<Vec<&str>>();
+            let error = |message: &str| GraphError::Parse {
+                line,
+                message: message.to_string(),
+            };
+
+            match tokens.first() {
+                None | Some(&"c") => continue,
+                Some(&"p") => {
+                    if graph.is_some() {
+                        return Err(error("repeated problem line"));
+                    }
+                    if tokens.len() < 4 || (tokens[1] != "edge" && tokens[1] != "col") {
+                        return Err(error("expected `p edge <vertices> <edges>`"));
+                    }
+                    let n = tokens[2]
+                        .parse::<usize>()
+                        .ok()
+                        .filter(|&n| n <= MAX_PARSED_VERTICES)
+                        .ok_or_else(|| error("invalid number of vertices"))?;
+                    graph = Some(Graph::empty(n));
+                }
+                Some(&"e") => {
+                    let graph = graph
+                        .as_mut()
+                        .ok_or_else(|| error("edge before the problem line"))?;
+                    if tokens.len() < 3 {
+                        return Err(error("expected `e <vertex> <vertex>`"));
+                    }
+                    let u = parse_vertex(tokens[1], Indexing::OneBased, line)?;
+                    let v = parse_vertex(tokens[2], Indexing::OneBased, line)?;
+                    let n = graph.num_of_vertices();
+                    if u >= n || v >= n {
+                        return Err(error(&format!(
+                            "edge ({}, {}) exceeds the {} declared vertices",
+                            u + 1,
+                            v + 1,
+                            n
+                        )));
+                    }
+                    add_parsed_edge(graph, u, v, line)?;
+                }
+                Some(token) => return Err(error(&format!("unknown line type `{token}`"))),
+            }
+        }
+
+        graph.ok_or(GraphError::Parse {
+            line: text.lines().count(),
+            message: "missing problem line".to_string(),
+        })
+    }
+
+    /// Formats the graph in the DIMACS format.
+    ///
+    /// # Returns
+    /// * A string with the problem line followed by the sorted edges, using indices starting from 1.
+    pub fn to_dimacs(&self) -> String {
+        let mut edges = self.all_edges();
+        edges.sort_unstable();
+
+        let mut text = format!(
+            "p edge {} {}\n",
+            self.num_of_vertices(),
+            self.num_of_edges()
+        );
+        for (u, v) in edges {
+            text.push_str(&format!("e {} {}\n", u + 1, v + 1));
+        }
+        text
+    }
+
+    /// Reads a graph from a DIMACS file.
+    ///
+    /// # Arguments
+    /// * `filename` - The name of the file to read the graph from.
+    ///
+    /// # Returns
+    /// * `Ok(Graph)` read from the file, or the `GraphError` describing why it could not be read.
+    pub fn read_dimacs(filename: &str) -> Result<Graph, GraphError> {
+        Graph::from_dimacs(&std::fs::read_to_string(filename)?)
+    }
+
+    /// Writes the graph to a DIMACS file.
+    ///
+    /// # Arguments
+    /// * `filename` - The name of the file to write the graph to.
+    ///
+    /// # Returns
+    /// * `Ok(())` if the file was written, `Err(GraphError::Io)` otherwise.
+    pub fn write_dimacs(&self, filename: &str) -> Result<(), GraphError> {
+        Ok(std::fs::write(filename, self.to_dimacs())?)
+    }
+}
@@ -1,7 +1,13 @@
+use crate::constraints::{ConstraintError, EdgeConstraints};
 use crate::graphs::Graph;
+use crate::ilp::*;
 use crate::mps_alg::*;
+use crate::planarity::PlanarEmbedding;
+use crate::post_processing::maximalize;
+use crate::weights::EdgeWeights;
 use good_lp::*;
 use std::collections::HashMap;
+use std::time::{Duration, Instant};
 
 /// Sums the variables associated with the given edges.
 ///
@@ -126,6 +132,207 @@ fn edge(arc: (usize, usize)) -> (usize, usize) {
 /// # Returns
 /// * The maximum planar subgraph of the input graph.
 pub fn facial_walks_mps(g: &Graph) -> Graph {
+    facial_walks_mps_with_time_limit(g, None).mps
+}
+
+/// Computes the maximum planar subgraph using facial walks, stopping the solver after the time limit.
+///
+/// # Arguments
+/// * `g` - The input graph.
+/// * `time_limit` - The optional wall-clock limit for the solver.
+///
+/// # Returns
+/// * The best planar subgraph found together with the proven upper bound and the gap.
+pub fn facial_walks_mps_with_time_limit(g: &Graph, time_limit: Option<Duration>) -> IlpSolution {
+    let options = IlpOptions {
+        time_limit,
+        ..Default::default()
+    };
+    facial_walks_mps_with_options(g, &options)
+        .expect("a program without fixed edges is always feasible")
+}
+
+/// Computes the maximum planar subgraph using facial walks, starting the solver from a planar subgraph.
+///
+/// If the subgraph is connected and has no vertices of degree one, the face and cyclic order variables are derived
+/// from its planar embedding. Otherwise only the edges are fixed and the solver completes the remaining variables.
+///
+/// # Arguments
+/// * `g` - The input graph.
+/// * `initial` - A planar subgraph of the input graph, e.g. computed by a heuristic.
+/// * `time_limit` - The optional wall-clock limit for the solver.
+///
+/// # Returns
+/// * The best planar subgraph found, never smaller than the initial one, together with the proven upper bound and
+///   the gap.
+pub fn facial_walks_mps_with_initial_solution(
+    g: &Graph,
+    initial: &Graph,
+    time_limit: Option<Duration>,
+) -> IlpSolution {
+    let options = IlpOptions {
+        initial: Some(initial.clone()),
+        time_limit,
+        ..Default::default()
+    };
+    facial_walks_mps_with_options(g, &options)
+        .expect("a program without fixed edges is always feasible")
+}
+
+/// Computes a maximum weight planar subgraph using facial walks.
+///
+/// An initial solution that is not a planar subgraph of `g` satisfying the constraints is ignored.
+///
+/// # Arguments
+/// * `g` - The input graph.
+/// * `options` - A reference to the weights of the edges, the fixed edges, the initial solution and the time limit.
+///
+/// # Returns
+/// * `Ok(IlpSolution)` containing the best planar subgraph found, never lighter than the initial one, together with
+///   the proven upper bound and the gap, or the `ConstraintError` if no planar subgraph satisfies the constraints.
+pub fn facial_walks_mps_with_options(
+    g: &Graph,
+    options: &IlpOptions,
+) -> Result<IlpSolution, ConstraintError> {
+    Ok(facial_walks_embedding_with_options(g, options)?.solution)
+}
+
+/// A maximum planar subgraph found by the facial walks algorithm together with its embedding.
+#[derive(Clone, Debug)]
+pub struct FacialWalksEmbedding {
+    /// The solution of the integer program.
+    pub solution: IlpSolution,
+    /// The faces of the subgraph, each given as the closed walk of arcs along its boundary.
+    pub faces: Vec<Vec<(usize, usize)>>,
+    /// The clockwise cyclic order of neighbors in the subgraph around each vertex.
+    pub rotation: Vec<Vec<usize>>,
+}
+
+impl FacialWalksEmbedding {
+    /// Returns the embedding of the subgraph.
+    ///
+    /// # Returns
+    /// * The planar embedding given by the rotation system.
+    pub fn embedding(&self) -> PlanarEmbedding {
+        PlanarEmbedding::from_rotation_system(self.rotation.clone())
+    }
+}
+
+/// Reads the rotation system of the subgraph from the cyclic order variables.
+///
+/// # Arguments
+/// * `mps` - A reference to the subgraph selected by the solution.
+/// * `successor` - A function telling if `p_v,u,w` is set in the solution.
+///
+/// # Returns
+/// * `Some(Vec<Vec<usize>>)` containing the clockwise order of neighbors around each vertex, or `None` if the
+///   variables do not form a single cycle around some vertex.
+fn rotation_from_solution<F>(mps: &Graph, successor: F) -> Option<Vec<Vec<usize>>>
+where
+    F: Fn(usize, usize, usize) -> bool,
+{
+    let mut rotation = Vec::with_capacity(mps.num_of_vertices());
+
+    for v in mps.vertices() {
+        let mut neighbors = mps
+            .neighbors(v)
+            .unwrap()
+            .iter()
+            .copied()
+            .collect::<Vec<_>>();
+        neighbors.sort_unstable();
+
+        let mut order = neighbors.iter().copied().take(1).collect::<Vec<usize>>();
+        while order.len() < neighbors.len() {
+            let u = order[order.len() - 1];
+            let w = neighbors
+                .iter()
+                .copied()
+                .find(|&w| w != u && successor(v, u, w))?;
+            if order.contains(&w) {
+                return None;
+            }
+            order.push(w);
+        }
+
+        // w follows u when the faces continue with the neighbor preceding u in the clockwise order
+        if order.len() > 1 {
+            order[1..].reverse();
+        }
+        rotation.push(order);
+    }
+
+    Some(rotation)
+}
+
+/// Reads the faces of the subgraph from the face assignment variables.
+///
+/// Every face is traced from its smallest arc, always continuing with the arc leaving the head of the current one
+/// with the neighbor preceding its tail in the clockwise order.
+///
+/// # Arguments
+/// * `rotation` - A slice containing the clockwise order of neighbors around each vertex.
+/// * `face_arcs` - A vector with the set of arcs assigned to each existing face.
+///
+/// # Returns
+/// * `Some(Vec<Vec<(usize, usize)>>)` containing the faces, or `None` if the arcs of some face are not closed walks
+///   of the rotation system.
+fn faces_from_solution(
+    rotation: &[Vec<usize>],
+    face_arcs: Vec<Vec<(usize, usize)>>,
+) -> Option<Vec<Vec<(usize, usize)>>> {
+    let predecessor = |v: usize, u: usize| {
+        let order = &rotation[v];
+        let j = order.iter().position(|&r| r == u)?;
+        Some(order[(j + order.len() - 1) % order.len()])
+    };
+    let mut faces = Vec::new();
+
+    for mut arcs in face_arcs {
+        arcs.sort_unstable();
+        while let Some(&start) = arcs.first() {
+            let mut face = Vec::new();
+            let mut arc = start;
+
+            loop {
+                let j = arcs.binary_search(&arc).ok()?;
+                arcs.remove(j);
+                face.push(arc);
+                arc = (arc.1, predecessor(arc.1, arc.0)?);
+                if arc == start {
+                    break;
+                }
+            }
+            faces.push(face);
+        }
+    }
+
+    Some(faces)
+}
+
+/// Computes a maximum weight planar subgraph using facial walks, together with its embedding.
+///
+/// The faces and the rotation system are read from the face assignment and cyclic order variables of the solution,
+/// so no separate planarity embedder is needed. If the initial subgraph is kept instead of the solution of the
+/// solver, it is embedded by the left-right planarity test.
+///
+/// The model cannot represent subgraphs with bridges, so it has no solution if the constraints force one. The
+/// mandatory edges are then extended to a maximal planar subgraph instead, which carries no optimality guarantee.
+///
+/// # Arguments
+/// * `g` - The input graph.
+/// * `options` - A reference to the weights of the edges, the fixed edges, the initial solution and the time limit.
+///
+/// # Returns
+/// * `Ok(FacialWalksEmbedding)` containing the solution, the faces and the rotation system of the subgraph, or the
+///   `ConstraintError` if no planar subgraph satisfies the constraints.
+pub fn facial_walks_embedding_with_options(
+    g: &Graph,
+    options: &IlpOptions,
+) -> Result<FacialWalksEmbedding, ConstraintError> {
+    let mandatory = options.constraints.validate(g)?;
+    let weights = &options.weights;
+    let initial = options.valid_initial(g);
     let mut vars = ProblemVariables::new();
 
     let n = g.num_of_vertices();
@@ -175,7 +382,8 @@ pub fn facial_walks_mps(g: &Graph) -> Graph {
         }
     }
 
-    let objective = sum_s_over_e(&edges, &s);
+    let objective = weighted_sum(&edges, &s, weights);
+    let columns = column_indices(&vars);
     let mut problem = vars.maximise(objective).using(highs);
 
     // Euler criterion
@@ -300,32 +508,166 @@ pub fn facial_walks_mps(g: &Graph) -> Graph {
         }
     }
 
-    let solution = problem.solve().unwrap();
+    problem = fix_edges(problem, &s, &options.constraints);
 
-    let mut mps = Graph::empty(n);
+    let mut initial_solution = HashMap::new();
 
-    for &e in edges.iter() {
-        if solution.value(s[&e]) >= 0.5 {
-            mps.add_edge(e.0, e.1);
+    if let Some(h) = initial {
+        select_edges(&mut initial_solution, &s, h);
+
+        // the faces of a connected subgraph without vertices of degree one satisfy 1a and 1d
+        let embedding = h.planar_embedding().filter(|embedding| {
+            embedding.num_of_faces() + n == 2 + h.num_of_edges()
+                && h.vertices().all(|v| h.degree(v) >= 2)
+        });
+
+        if let Some(embedding) = embedding {
+            let faces = embedding.faces();
+
+            for (i, &x_i) in x.iter().enumerate() {
+                let value = if i < faces.len() { 1.0 } else { 0.0 };
+                initial_solution.insert(x_i, value);
+            }
+
+            for c_i in c.iter() {
+                for &c_i_a in c_i.values() {
+                    initial_solution.insert(c_i_a, 0.0);
+                }
+            }
+
+            for (i, face) in faces.iter().enumerate() {
+                for j in 0..face.len() {
+                    let a = (face[j], face[(j + 1) % face.len()]);
+                    initial_solution.insert(c[i][&a], 1.0);
+                }
+            }
+
+            // the faces continue with the neighbor preceding the previous vertex in the clockwise order
+            for (&(v, u, w), &p_v_u_w) in p.iter() {
+                let rotation = embedding.rotation(v).unwrap();
+                let value = match rotation.iter().position(|&r| r == u) {
+                    Some(j) if rotation[(j + rotation.len() - 1) % rotation.len()] == w => 1.0,
+                    _ => 0.0,
+                };
+                initial_solution.insert(p_v_u_w, value);
+            }
+        }
+    }
+
+    let SolverOutcome {
+        mut mps,
+        dual_bound,
+        time_limited,
+        infeasible,
+        mut values,
+    } = solve_with_time_limit(
+        g,
+        weights,
+        problem,
+        &columns,
+        &s,
+        &initial_solution,
+        options.time_limit,
+    );
+
+    if infeasible {
+        mps = maximalize(g, &mandatory, weights, &options.constraints)
+            .expect("the mandatory edges are planar");
+    }
+
+    if let Some(h) = initial {
+        if weights.total_weight(h) > weights.total_weight(&mps) {
+            mps = h.clone();
+            values = None;
         }
     }
 
-    mps
+    let embedding = values.and_then(|values| {
+        let rotation =
+            rotation_from_solution(&mps, |v, u, w| values[columns[&p[&(v, u, w)]]] >= 0.5)?;
+        let face_arcs = (0..f_max)
+            .filter(|&i| values[columns[&x[i]]] >= 0.5)
+            .map(|i| {
+                arcs.iter()
+                    .copied()
+                    .filter(|a| values[columns[&c[i][a]]] >= 0.5)
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        let faces = faces_from_solution(&rotation, face_arcs)?;
+        Some((faces, rotation))
+    });
+
+    // the initial subgraph is embedded separately, as are solutions with inconsistent variables
+    let (faces, rotation) = embedding.unwrap_or_else(|| {
+        let embedding = mps
+            .planar_embedding()
+            .expect("the subgraph found is planar");
+        let faces = embedding
+            .faces()
+            .iter()
+            .map(|face| {
+                (0..face.len())
+                    .map(|j| (face[j], face[(j + 1) % face.len()]))
+                    .collect()
+            })
+            .collect();
+        (faces, embedding.rotation_system().clone())
+    });
+
+    Ok(FacialWalksEmbedding {
+        solution: IlpSolution::new(
+            g,
+            weights,
+            &options.constraints,
+            mps,
+            dual_bound,
+            time_limited,
+        ),
+        faces,
+        rotation,
+    })
 }
 
 /// A struct representing the facial walks MPS algorithm.
-pub struct FacialWalksMps {}
+#[derive(Default)]
+pub struct FacialWalksMps {
+    /// The optional wall-clock limit for the solver.
+    pub time_limit: Option<Duration>,
+    /// The optional heuristic whose solution is used as the initial solution.
+    pub warm_start: Option<Box<dyn MpsAlgorithm>>,
+}
 
 impl MpsAlgorithm for FacialWalksMps {
-    /// Computes the maximum planar subgraph of the given graph.
+    /// Computes a maximum weight planar subgraph of the given graph satisfying the fixed-edge constraints.
     ///
     /// # Arguments
     /// * `g` - The input graph.
+    /// * `weights` - A reference to the weights of the edges.
+    /// * `constraints` - A reference to the mandatory and forbidden edges.
     ///
     /// # Returns
-    /// * The maximum planar subgraph of the input graph.
-    fn maximum_planar_subgraph(&self, g: &Graph) -> Graph {
-        facial_walks_mps(g)
+    /// * `Ok(MpsResult)` containing the subgraph, its status and the upper bound, or the `ConstraintError` if no
+    ///   planar subgraph satisfies the constraints.
+    fn solve_constrained(
+        &self,
+        g: &Graph,
+        weights: &EdgeWeights,
+        constraints: &EdgeConstraints,
+    ) -> Result<MpsResult, ConstraintError> {
+        let start = Instant::now();
+        let options = IlpOptions {
+            weights: weights.clone(),
+            constraints: constraints.clone(),
+            initial: match &self.warm_start {
+                Some(heuristic) => Some(heuristic.solve_constrained(g, weights, constraints)?.mps),
+                None => None,
+            },
+            time_limit: self.time_limit,
+        };
+        let solution = facial_walks_mps_with_options(g, &options)?;
+
+        Ok(MpsResult::exact(g, solution, start.elapsed()))
     }
 
     /// Returns the name of the algorithm.
@@ -0,0 +1,582 @@
+use crate::edge_list::add_parsed_edge;
+use crate::graphs::{Graph, GraphError};
+use std::collections::HashMap;
+
+/// An edge read from a GraphML or GML file before its ends are resolved.
+struct ParsedEdge {
+    source: String,
+    target: String,
+    planar: Option<bool>,
+    line: usize,
+}
+
+/// Builds a parse error.
+///
+/// # Arguments
+/// * `line` - The number of the line, starting from 1.
+/// * `message` - The description of the error.
+///
+/// # Returns
+/// * A `GraphError::Parse` with the given line and message.
+fn parse_error(line: usize, message: String) -> GraphError {
+    GraphError::Parse { line, message }
+}
+
+/// Parses the value of a `planar` attribute.
+///
+/// # Arguments
+/// * `value` - The value of the attribute.
+/// * `line` - The number of the line, starting from 1, used in errors.
+///
+/// # Returns
+/// * `Ok(bool)` containing the value, or `Err(GraphError::Parse)` if it is not a boolean.
+fn parse_planar(value: &str, line: usize) -> Result<bool, GraphError> {
+    match value.trim() {
+        "true" | "1" => Ok(true),
+        "false" | "0" => Ok(false),
+        value => Err(parse_error(line, format!("`{value}` is not a boolean"))),
+    }
+}
+
+/// Builds the graph and the overlay subgraph from parsed nodes and edges.
+///
+/// # Arguments
+/// * `nodes` - A vector of node identifiers in the order of appearance, which becomes the order of the vertices.
+/// * `edges` - A vector of the parsed edges.
+///
+/// # Returns
+/// * `Ok((Graph, Option<Graph>))` containing the graph and the subgraph of the edges marked as planar if any edge is
+///   marked, or `Err(GraphError::Parse)` if an edge refers to an unknown node or is a self loop.
+fn build_graph(
+    nodes: Vec<(String, usize)>,
+    edges: Vec<ParsedEdge>,
+) -> Result<(Graph, Option<Graph>), GraphError> {
+    let n = nodes.len();
+    let mut index = HashMap::new();
+    for (i, (id, line)) in nodes.into_iter().enumerate() {
+        if index.insert(id.clone(), i).is_some() {
+            return Err(parse_error(line, format!("node `{id}` is declared twice")));
+        }
+    }
+
+    let mut graph = Graph::empty(n);
+    let mut overlay = Graph::empty(n);
+    let mut annotated = false;
+
+    for edge in edges {
+        let resolve = |id: &str| {
+            index
+                .get(id)
+                .copied()
+                .ok_or_else(|| parse_error(edge.line, format!("unknown node `{id}`")))
+        };
+        let u = resolve(&edge.source)?;
+        let v = resolve(&edge.target)?;
+        add_parsed_edge(&mut graph, u, v, edge.line)?;
+
+        if let Some(planar) = edge.planar {
+            annotated = true;
+            if planar {
+                overlay.add_edge(u, v);
+            }
+        }
+    }
+
+    Ok((graph, annotated.then_some(overlay)))
+}
+
+/// A tag of an XML document.
+struct XmlTag {
+    name: String,
+    attributes: HashMap<String, String>,
+    closing: bool,
+    self_closing: bool,
+    line: usize,
+    /// The text between this tag and the next one.
+    text: String,
+}
+
+/// Replaces the predefined XML entities.
+///
+/// # Arguments
+/// * `text` - The escaped text.
+///
+/// # Returns
+/// * The unescaped text.
+fn xml_unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Finds the end of a tag, skipping `>` characters inside quoted attribute values.
+///
+/// # Arguments
+/// * `text` - The text starting with the `<` of the tag.
+///
+/// # Returns
+/// * `Some(usize)` with the index of the closing `>`, or `None` if the tag is unterminated.
+fn tag_end(text: &str) -> Option<usize> {
+    let mut quote = None;
+
+    for (i, c) in text.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '>' => return Some(i),
+            None => {}
+        }
+    }
+
+    None
+}
+
+/// Splits an XML document into tags.
+///
+/// Only the subset of XML used by GraphML is supported: the declaration, comments and doctypes are skipped,
+/// and CDATA sections are not recognized.
+///
+/// # Arguments
+/// * `text` - The XML document.
+///
+/// # Returns
+/// * `Ok(Vec<XmlTag>)` containing the tags in order, or `Err(GraphError::Parse)` if a tag is malformed.
+fn xml_tags(text: &str) -> Result<Vec<XmlTag>, GraphError> {
+    let mut tags = Vec::<XmlTag>::new();
+    let mut rest = text;
+    let mut line = 1;
+
+    while let Some(start) = rest.find('<') {
+        if let Some(tag) = tags.last_mut() {
+            tag.text = xml_unescape(&rest[..start]);
+        }
+        line += rest[..start].matches('\n').count();
+        rest = &rest[start..];
+
+        let end = if rest.starts_with("<!--") {
+            rest.find("-->").map(|end| end + 3)
+        } else if rest.starts_with("<?") {
+            rest.find("?>").map(|end| end + 2)
+        } else {
+            tag_end(rest).map(|end| end + 1)
+        };
+        let end = end.ok_or_else(|| parse_error(line, "unterminated tag".to_string()))?;
+        let content = &rest[1..end - 1];
+        let tag_line = line;
+        line += rest[..end].matches('\n').count();
+        rest = &rest[end..];
+
+        if content.starts_with('!') || content.starts_with('?') {
+            continue;
+        }
+
+        let closing = content.starts_with('/');
+        let self_closing = content.ends_with('/');
+        let content = content.trim_start_matches('/').trim_end_matches('/');
+        let name_end = content.find(char::is_whitespace).unwrap_or(content.len());
+        let mut attributes = HashMap::new();
+        let mut attribute_text = content[name_end..].trim_start();
+
+        while !attribute_text.is_empty() {
+            let invalid = || parse_error(tag_line, format!("invalid attributes in `<{content}>`"));
+            let eq = attribute_text.find('=').ok_or_else(invalid)?;
+            let key = attribute_text[..eq].trim();
+            let value_text = attribute_text[eq + 1..].trim_start();
+            let quote = value_text
+                .chars()
+                .next()
+                .filter(|c| *c == '"' || *c == '\'');
+            let quote = quote.ok_or_else(invalid)?;
+            let value_end = value_text[1..].find(quote).ok_or_else(invalid)? + 1;
+            attributes.insert(key.to_string(), xml_unescape(&value_text[1..value_end]));
+            attribute_text = value_text[value_end + 1..].trim_start();
+        }
+
+        tags.push(XmlTag {
+            name: content[..name_end].to_string(),
+            attributes,
+            closing,
+            self_closing,
+            line: tag_line,
+            text: String::new(),
+        });
+    }
+
+    Ok(tags)
+}
+
+/// A value of a GML key.
+enum GmlValue {
+    /// A number or a string.
+    Scalar(String),
+    /// A list of key-value pairs with the line of each key.
+    List(Vec<(String, GmlValue, usize)>),
+}
+
+/// Splits a GML document into tokens.
+///
+/// # Arguments
+/// * `text` - The GML document.
+///
+/// # Returns
+/// * `Ok(Vec<(String, usize)>)` containing the tokens and their lines, with the quotes of strings kept, or
+///   `Err(GraphError::Parse)` if a string is unterminated.
+fn gml_tokens(text: &str) -> Result<Vec<(String, usize)>, GraphError> {
+    let mut tokens = Vec::new();
+
+    for (i, content) in text.lines().enumerate() {
+        let mut chars = content.char_indices().peekable();
+        while let Some((start, c)) = chars.next() {
+            if c.is_whitespace() {
+                continue;
+            }
+            if c == '#' {
+                break;
+            }
+
+            let mut end = start + c.len_utf8();
+            if c == '"' {
+                let (close, _) = chars
+                    .find(|&(_, c)| c == '"')
+                    .ok_or_else(|| parse_error(i + 1, "unterminated string".to_string()))?;
+                end = close + 1;
+            } else if c != '[' && c != ']' {
+                while let Some(&(j, c)) = chars.peek() {
+                    if c.is_whitespace() || c == '[' || c == ']' {
+                        break;
+                    }
+                    end = j + c.len_utf8();
+                    chars.next();
+                }
+            }
+            tokens.push((content[start..end].to_string(), i + 1));
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Parses a list of GML key-value pairs.
+///
+/// # Arguments
+/// * `tokens` - An iterator over the tokens and their lines.
+/// * `nested` - Whether the list is enclosed in brackets, so it ends with `]`.
+///
+/// # Returns
+/// * `Ok(Vec<(String, GmlValue, usize)>)` containing the pairs, or `Err(GraphError::Parse)` if the list is malformed.
+fn parse_gml_list<I>(
+    tokens: &mut I,
+    nested: bool,
+) -> Result<Vec<(String, GmlValue, usize)>, GraphError>
+where
+    I: Iterator<Item = (String, usize)>,
+{
+    let mut list = Vec::new();
+    let mut last_line = 1;
+
+    while let Some((key, line)) = tokens.next() {
+        last_line = line;
+        if key == "]" {
+            if nested {
+                return Ok(list);
+            }
+            return Err(parse_error(line, "unexpected `]`".to_string()));
+        }
+
+        let value = match tokens.next() {
+            Some((token, _)) if token == "[" => GmlValue::List(parse_gml_list(tokens, true)?),
+            Some((token, _)) if token != "]" => {
+                GmlValue::Scalar(token.trim_matches('"').to_string())
+            }
+            _ => return Err(parse_error(line, format!("missing value of `{key}`"))),
+        };
+        list.push((key, value, line));
+    }
+
+    if nested {
+        return Err(parse_error(last_line, "missing `]`".to_string()));
+    }
+    Ok(list)
+}
+
+impl Graph {
+    /// Formats the graph as GraphML.
+    ///
+    /// The vertices become nodes `n0`, `n1`, ... If an overlay is given, every edge gets a boolean `planar`
+    /// attribute telling if it belongs to the overlay, e.g. to a maximum planar subgraph.
+    ///
+    /// # Arguments
+    /// * `overlay` - An optional subgraph whose edges are marked as planar.
+    ///
+    /// # Returns
+    /// * The GraphML document.
+    pub fn to_graphml(&self, overlay: Option<&Graph>) -> String {
+        let mut text = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        text.push_str("<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n");
+        if overlay.is_some() {
+            text.push_str(
+                "  <key id=\"planar\" for=\"edge\" attr.name=\"planar\" attr.type=\"boolean\"/>\n",
+            );
+        }
+        text.push_str("  <graph id=\"G\" edgedefault=\"undirected\">\n");
+
+        for v in 0..self.num_of_vertices() {
+            text.push_str(&format!("    <node id=\"n{v}\"/>\n"));
+        }
+        for (u, v) in self.all_edges() {
+            match overlay {
+                Some(h) => text.push_str(&format!(
+                    "    <edge source=\"n{u}\" target=\"n{v}\">\n      <data key=\"planar\">{}</data>\n    </edge>\n",
+                    h.has_edge(u, v)
+                )),
+                None => text.push_str(&format!("    <edge source=\"n{u}\" target=\"n{v}\"/>\n")),
+            }
+        }
+
+        text.push_str("  </graph>\n</graphml>\n");
+        text
+    }
+
+    /// Parses a graph from GraphML, together with the subgraph of the edges marked as planar.
+    ///
+    /// The vertices are numbered in the order their nodes appear. The `planar` edge attribute is recognized by its
+    /// `attr.name` among the keys for edges or all elements, so files edited in other tools can be read back. Edges
+    /// without a value take the `<default>` of the key, if it has one. Directions of edges are ignored and repeated
+    /// edges are read once.
+    ///
+    /// # Arguments
+    /// * `text` - The GraphML document.
+    ///
+    /// # Returns
+    /// * `Ok((Graph, Option<Graph>))` containing the graph and the subgraph of the edges marked as planar if any edge
+    ///   is marked, or `Err(GraphError::Parse)` pointing to the first invalid line.
+    pub fn from_graphml_with_overlay(text: &str) -> Result<(Graph, Option<Graph>), GraphError> {
+        let tags = xml_tags(text)?;
+        let mut planar_keys = HashMap::new();
+
+        for (i, tag) in tags.iter().enumerate() {
+            let is_planar = tag.name == "key"
+                && !tag.closing
+                && tag
+                    .attributes
+                    .get("attr.name")
+                    .is_some_and(|name| name == "planar")
+                && tag
+                    .attributes
+                    .get("for")
+                    .is_none_or(|domain| domain == "edge" || domain == "all");
+            let Some(id) = tag.attributes.get("id").filter(|_| is_planar) else {
+                continue;
+            };
+
+            let default = if tag.self_closing {
+                None
+            } else {
+                tags[i + 1..]
+                    .iter()
+                    .take_while(|tag| !(tag.closing && tag.name == "key"))
+                    .find(|tag| tag.name == "default" && !tag.closing)
+                    .map(|tag| parse_planar(&tag.text, tag.line))
+                    .transpose()?
+            };
+            planar_keys.insert(id.clone(), default);
+        }
+        let default_planar = planar_keys.values().find_map(|&default| default);
+
+        let mut nodes = Vec::new();
+        let mut edges = Vec::<ParsedEdge>::new();
+        let mut in_edge = false;
+
+        for tag in tags.iter() {
+            if tag.closing {
+                in_edge &= tag.name != "edge";
+                continue;
+            }
+
+            let attribute = |key: &str| {
+                tag.attributes.get(key).cloned().ok_or_else(|| {
+                    parse_error(tag.line, format!("`<{}>` without `{key}`", tag.name))
+                })
+            };
+
+            match tag.name.as_str() {
+                "node" => nodes.push((attribute("id")?, tag.line)),
+                "edge" => {
+                    edges.push(ParsedEdge {
+                        source: attribute("source")?,
+                        target: attribute("target")?,
+                        planar: default_planar,
+                        line: tag.line,
+                    });
+                    in_edge = !tag.self_closing;
+                }
+                "data" if in_edge && planar_keys.contains_key(&attribute("key")?) => {
+                    if let Some(edge) = edges.last_mut() {
+                        edge.planar = Some(parse_planar(&tag.text, tag.line)?);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        build_graph(nodes, edges)
+    }
+
+    /// Parses a graph from GraphML, ignoring the `planar` attributes.
+    ///
+    /// # Arguments
+    /// * `text` - The GraphML document.
+    ///
+    /// # Returns
+    /// * `Ok(Graph)` described by the document, or `Err(GraphError::Parse)` pointing to the first invalid line.
+    pub fn from_graphml(text: &str) -> Result<Graph, GraphError> {
+        Ok(Graph::from_graphml_with_overlay(text)?.0)
+    }
+
+    /// Reads a graph and the subgraph of the edges marked as planar from a GraphML file.
+    ///
+    /// # Arguments
+    /// * `filename` - The name of the file to read the graph from.
+    ///
+    /// # Returns
+    /// * `Ok((Graph, Option<Graph>))` containing the graph and the marked subgraph, or the `GraphError` describing
+    ///   why they could not be read.
+    pub fn read_graphml(filename: &str) -> Result<(Graph, Option<Graph>), GraphError> {
+        Graph::from_graphml_with_overlay(&std::fs::read_to_string(filename)?)
+    }
+
+    /// Writes the graph to a GraphML file.
+    ///
+    /// # Arguments
+    /// * `filename` - The name of the file to write the graph to.
+    /// * `overlay` - An optional subgraph whose edges are marked as planar.
+    ///
+    /// # Returns
+    /// * `Ok(())` if the file was written, or the `GraphError` describing why it could not be.
+    pub fn write_graphml(&self, filename: &str, overlay: Option<&Graph>) -> Result<(), GraphError> {
+        Ok(std::fs::write(filename, self.to_graphml(overlay))?)
+    }
+
+    /// Formats the graph as GML.
+    ///
+    /// The vertices become nodes with their indices as ids and labels. If an overlay is given, every edge gets a
+    /// `planar` attribute equal to `"true"` or `"false"`.
+    ///
+    /// # Arguments
+    /// * `overlay` - An optional subgraph whose edges are marked as planar.
+    ///
+    /// # Returns
+    /// * The GML document.
+    pub fn to_gml(&self, overlay: Option<&Graph>) -> String {
+        let mut text = String::from("graph [\n  directed 0\n");
+
+        for v in 0..self.num_of_vertices() {
+            text.push_str(&format!("  node [\n    id {v}\n    label \"{v}\"\n  ]\n"));
+        }
+        for (u, v) in self.all_edges() {
+            text.push_str(&format!("  edge [\n    source {u}\n    target {v}\n"));
+            if let Some(h) = overlay {
+                text.push_str(&format!("    planar \"{}\"\n", h.has_edge(u, v)));
+            }
+            text.push_str("  ]\n");
+        }
+
+        text.push_str("]\n");
+        text
+    }
+
+    /// Parses a graph from GML, together with the subgraph of the edges marked as planar.
+    ///
+    /// The vertices are numbered in the order their nodes appear. The `planar` edge attribute may be a string or
+    /// an integer. Directions of edges are ignored and repeated edges are read once.
+    ///
+    /// # Arguments
+    /// * `text` - The GML document.
+    ///
+    /// # Returns
+    /// * `Ok((Graph, Option<Graph>))` containing the graph and the subgraph of the edges marked as planar if any edge
+    ///   is marked, or `Err(GraphError::Parse)` pointing to the first invalid line.
+    pub fn from_gml_with_overlay(text: &str) -> Result<(Graph, Option<Graph>), GraphError> {
+        let mut tokens = gml_tokens(text)?.into_iter();
+        let document = parse_gml_list(&mut tokens, false)?;
+        let graph = document
+            .iter()
+            .find_map(|(key, value, _)| match value {
+                GmlValue::List(list) if key == "graph" => Some(list),
+                _ => None,
+            })
+            .ok_or_else(|| parse_error(1, "missing `graph`".to_string()))?;
+
+        let mut nodes = Vec::new();
+        let mut edges = Vec::new();
+
+        for (key, value, line) in graph {
+            let GmlValue::List(list) = value else {
+                continue;
+            };
+            let attribute = |name: &str| {
+                list.iter().find_map(|(key, value, _)| match value {
+                    GmlValue::Scalar(value) if key == name => Some(value.clone()),
+                    _ => None,
+                })
+            };
+            let required = |name: &str| {
+                attribute(name)
+                    .ok_or_else(|| parse_error(*line, format!("`{key}` without `{name}`")))
+            };
+
+            match key.as_str() {
+                "node" => nodes.push((required("id")?, *line)),
+                "edge" => edges.push(ParsedEdge {
+                    source: required("source")?,
+                    target: required("target")?,
+                    planar: attribute("planar")
+                        .map(|value| parse_planar(&value, *line))
+                        .transpose()?,
+                    line: *line,
+                }),
+                _ => {}
+            }
+        }
+
+        build_graph(nodes, edges)
+    }
+
+    /// Parses a graph from GML, ignoring the `planar` attributes.
+    ///
+    /// # Arguments
+    /// * `text` - The GML document.
+    ///
+    /// # Returns
+    /// * `Ok(Graph)` described by the document, or `Err(GraphError::Parse)` pointing to the first invalid line.
+    pub fn from_gml(text: &str) -> Result<Graph, GraphError> {
+        Ok(Graph::from_gml_with_overlay(text)?.0)
+    }
+
+    /// Reads a graph and the subgraph of the edges marked as planar from a GML file.
+    ///
+    /// # Arguments
+    /// * `filename` - The name of the file to read the graph from.
+    ///
+    /// # Returns
+    /// * `Ok((Graph, Option<Graph>))` containing the graph and the marked subgraph, or the `GraphError` describing
+    ///   why they could not be read.
+    pub fn read_gml(filename: &str) -> Result<(Graph, Option<Graph>), GraphError> {
+        Graph::from_gml_with_overlay(&std::fs::read_to_string(filename)?)
+    }
+
+    /// Writes the graph to a GML file.
+    ///
+    /// # Arguments
+    /// * `filename` - The name of the file to write the graph to.
+    /// * `overlay` - An optional subgraph whose edges are marked as planar.
+    ///
+    /// # Returns
+    /// * `Ok(())` if the file was written, or the `GraphError` describing why it could not be.
+    pub fn write_gml(&self, filename: &str, overlay: Option<&Graph>) -> Result<(), GraphError> {
+        Ok(std::fs::write(filename, self.to_gml(overlay))?)
+    }
+}
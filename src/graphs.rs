@@ -1,6 +1,7 @@
+use crate::planarity::{kuratowski_subgraph, lr_planar_embedding, PlanarEmbedding};
 use serde::{Deserialize, Serialize};
-use serde_json::json;
 use std::collections::HashSet;
+use std::fmt;
 use std::fs::File;
 use std::ops::Add;
 use std::ops::Range;
@@ -13,6 +14,84 @@ pub struct Graph {
     neighbors: Vec<HashSet<usize>>,
 }
 
+/// The largest number of vertices accepted by the parsers, so that a malformed file cannot exhaust the memory.
+pub(crate) const MAX_PARSED_VERTICES: usize = 1 << 24;
+
+/// An enum representing the errors of graph operations.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GraphError {
+    /// The vertex is not a vertex of the graph.
+    InvalidVertex(usize),
+    /// The edge would join a vertex with itself.
+    SelfLoop(usize),
+    /// The edge is already an edge of the graph.
+    DuplicateEdge(usize, usize),
+    /// The edge is not an edge of the graph.
+    MissingEdge(usize, usize),
+    /// The file could not be read or written.
+    Io(String),
+    /// The file is not a valid JSON representation of a graph.
+    Json(String),
+    /// A line of a text file does not follow the format.
+    Parse { line: usize, message: String },
+    /// The number of adjacency lists differs from the declared number of vertices.
+    VertexCountMismatch { declared: usize, found: usize },
+    /// The number of edges in the adjacency lists differs from the declared number of edges.
+    EdgeCountMismatch { declared: usize, found: usize },
+    /// A neighbor of the vertex is not a vertex of the graph.
+    NeighborOutOfRange { vertex: usize, neighbor: usize },
+    /// The neighbor appears more than once in the adjacency list of the vertex.
+    DuplicateNeighbor { vertex: usize, neighbor: usize },
+    /// The vertex lists the neighbor, but the neighbor does not list the vertex.
+    AsymmetricNeighbor { vertex: usize, neighbor: usize },
+}
+
+impl fmt::Display for GraphError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GraphError::InvalidVertex(v) => write!(f, "vertex {v} is not a vertex of the graph"),
+            GraphError::SelfLoop(v) => write!(f, "self loop at vertex {v}"),
+            GraphError::DuplicateEdge(u, v) => write!(f, "edge ({u}, {v}) already exists"),
+            GraphError::MissingEdge(u, v) => write!(f, "edge ({u}, {v}) does not exist"),
+            GraphError::Io(message) => write!(f, "i/o error: {message}"),
+            GraphError::Json(message) => write!(f, "invalid graph JSON: {message}"),
+            GraphError::Parse { line, message } => write!(f, "line {line}: {message}"),
+            GraphError::VertexCountMismatch { declared, found } => write!(
+                f,
+                "{declared} vertices declared, but {found} adjacency lists found"
+            ),
+            GraphError::EdgeCountMismatch { declared, found } => {
+                write!(f, "{declared} edges declared, but {found} edges found")
+            }
+            GraphError::NeighborOutOfRange { vertex, neighbor } => write!(
+                f,
+                "vertex {vertex} has neighbor {neighbor}, which is not a vertex of the graph"
+            ),
+            GraphError::DuplicateNeighbor { vertex, neighbor } => {
+                write!(f, "vertex {vertex} lists neighbor {neighbor} more than once")
+            }
+            GraphError::AsymmetricNeighbor { vertex, neighbor } => write!(
+                f,
+                "vertex {vertex} lists neighbor {neighbor}, but vertex {neighbor} does not list {vertex}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GraphError {}
+
+impl From<std::io::Error> for GraphError {
+    fn from(error: std::io::Error) -> Self {
+        GraphError::Io(error.to_string())
+    }
+}
+
+impl From<serde_json::Error> for GraphError {
+    fn from(error: serde_json::Error) -> Self {
+        GraphError::Json(error.to_string())
+    }
+}
+
 /// A struct for serializing and deserializing a graph to/from JSON.
 #[derive(Serialize, Deserialize)]
 struct GraphJson {
@@ -27,6 +106,23 @@ impl Default for Graph {
     }
 }
 
+impl fmt::Display for Graph {
+    /// Formats the graph as the list of its edges.
+    ///
+    /// # Arguments
+    /// * `f` - The formatter.
+    ///
+    /// # Returns
+    /// * A `fmt::Result` indicating the success or failure of the operation.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Edges:")?;
+        for (from, to) in self.all_edges() {
+            writeln!(f, "{} <-> {}", from, to)?;
+        }
+        Ok(())
+    }
+}
+
 impl Add for Graph {
     type Output = Self;
 
@@ -187,6 +283,38 @@ impl Graph {
         rustworkx_core::planar::is_planar(&petgraph)
     }
 
+    /// Computes a combinatorial planar embedding of the graph.
+    ///
+    /// # Returns
+    /// * `Some(PlanarEmbedding)` containing the rotation system and the faces if the graph is planar, `None` otherwise.
+    pub fn planar_embedding(&self) -> Option<PlanarEmbedding> {
+        lr_planar_embedding(self)
+    }
+
+    /// Finds a Kuratowski subgraph certifying that the graph is non-planar.
+    ///
+    /// # Returns
+    /// * `Some(Vec<(usize, usize)>)` containing the edges of a subdivision of K5 or K3,3 if the graph is non-planar,
+    ///   `None` otherwise.
+    pub fn kuratowski_subgraph(&self) -> Option<Vec<(usize, usize)>> {
+        kuratowski_subgraph(self)
+    }
+
+    /// Checks that a vertex is valid.
+    ///
+    /// # Arguments
+    /// * `vertex` - The index of the vertex.
+    ///
+    /// # Returns
+    /// * `Ok(())` if the vertex is valid, `Err(GraphError::InvalidVertex)` otherwise.
+    fn check_vertex(&self, vertex: usize) -> Result<(), GraphError> {
+        if self.is_valid_vertex(vertex) {
+            Ok(())
+        } else {
+            Err(GraphError::InvalidVertex(vertex))
+        }
+    }
+
     /// Checks if an edge exists between two vertices.
     ///
     /// # Arguments
@@ -194,9 +322,26 @@ impl Graph {
     /// * `to` - The index of the ending vertex.
     ///
     /// # Returns
-    /// * `true` if the edge exists, `false` otherwise.
+    /// * `Ok(bool)` telling whether the edge exists, or `Err(GraphError::InvalidVertex)` if a vertex is invalid.
+    pub fn try_has_edge(&self, from: usize, to: usize) -> Result<bool, GraphError> {
+        self.check_vertex(from)?;
+        self.check_vertex(to)?;
+        Ok(self.neighbors[from].contains(&to))
+    }
+
+    /// Checks if an edge exists between two vertices.
+    ///
+    /// A vertex outside the graph has no edges, so no error is raised for it. Callers that have to tell an invalid
+    /// vertex from a missing edge should use `try_has_edge`.
+    ///
+    /// # Arguments
+    /// * `from` - The index of the starting vertex.
+    /// * `to` - The index of the ending vertex.
+    ///
+    /// # Returns
+    /// * `true` if the edge exists, `false` otherwise, also for invalid vertices.
     pub fn has_edge(&self, from: usize, to: usize) -> bool {
-        self.neighbors[from].contains(&to)
+        self.try_has_edge(from, to).unwrap_or(false)
     }
 
     /// Adds a vertex to the graph.
@@ -216,25 +361,37 @@ impl Graph {
     /// * `to` - The index of the ending vertex.
     ///
     /// # Returns
-    /// * `true` if the edge is successfully added, `false` otherwise.
-    pub fn add_edge(&mut self, from: usize, to: usize) -> bool {
+    /// * `Ok(())` if the edge is added, or the `GraphError` telling why it cannot be.
+    pub fn try_add_edge(&mut self, from: usize, to: usize) -> Result<(), GraphError> {
         // both vertices must be valid
-        if !self.is_valid_vertex(from) || !self.is_valid_vertex(to) {
-            println!("Invalid vertices: {} -> {}", from, to);
-            return false;
-        }
+        self.check_vertex(from)?;
+        self.check_vertex(to)?;
 
         // no self loops nor multi-edges
-        if from == to || self.neighbors[from].contains(&to) {
-            println!("Self loop or multi-edge: {} -> {}", from, to);
-            return false;
+        if from == to {
+            return Err(GraphError::SelfLoop(from));
+        }
+        if self.neighbors[from].contains(&to) {
+            return Err(GraphError::DuplicateEdge(from, to));
         }
 
         self.neighbors[from].insert(to);
         self.neighbors[to].insert(from);
         self.num_of_edges += 1;
 
-        true
+        Ok(())
+    }
+
+    /// Adds an edge between two vertices.
+    ///
+    /// # Arguments
+    /// * `from` - The index of the starting vertex.
+    /// * `to` - The index of the ending vertex.
+    ///
+    /// # Returns
+    /// * `true` if the edge is successfully added, `false` otherwise.
+    pub fn add_edge(&mut self, from: usize, to: usize) -> bool {
+        self.try_add_edge(from, to).is_ok()
     }
 
     /// Removes an edge between two vertices.
@@ -244,25 +401,34 @@ impl Graph {
     /// * `to` - The index of the ending vertex.
     ///
     /// # Returns
-    /// * `true` if the edge is successfully removed, `false` otherwise.
-    pub fn remove_edge(&mut self, from: usize, to: usize) -> bool {
+    /// * `Ok(())` if the edge is removed, or the `GraphError` telling why it cannot be.
+    pub fn try_remove_edge(&mut self, from: usize, to: usize) -> Result<(), GraphError> {
         // both vertices must be valid
-        if !self.is_valid_vertex(from) || !self.is_valid_vertex(to) {
-            println!("Invalid vertices: {} -> {}", from, to);
-            return false;
-        }
+        self.check_vertex(from)?;
+        self.check_vertex(to)?;
 
         // must exist
         if !self.neighbors[from].contains(&to) {
-            println!("Edge does not exist: {} -> {}", from, to);
-            return false;
+            return Err(GraphError::MissingEdge(from, to));
         }
 
         self.neighbors[from].remove(&to);
         self.neighbors[to].remove(&from);
         self.num_of_edges -= 1;
 
-        true
+        Ok(())
+    }
+
+    /// Removes an edge between two vertices.
+    ///
+    /// # Arguments
+    /// * `from` - The index of the starting vertex.
+    /// * `to` - The index of the ending vertex.
+    ///
+    /// # Returns
+    /// * `true` if the edge is successfully removed, `false` otherwise.
+    pub fn remove_edge(&mut self, from: usize, to: usize) -> bool {
+        self.try_remove_edge(from, to).is_ok()
     }
 
     /// Returns the number of vertices in the graph.
@@ -348,13 +514,9 @@ impl Graph {
     }
 
     /// Prints all edges in the graph.
+    #[deprecated(note = "use the `Display` implementation, e.g. `print!(\"{graph}\")`")]
     pub fn print_edges(&self) {
-        println!("Edges:");
-        for (from, tos) in self.neighbors.iter().enumerate() {
-            for to in tos.iter().filter(|&to| from < *to) {
-                println!("{} <-> {}", from, to);
-            }
-        }
+        print!("{self}");
     }
 
     /// Writes the graph to a JSON file.
@@ -363,14 +525,29 @@ impl Graph {
     /// * `filename` - The name of the file to write the graph to.
     ///
     /// # Returns
-    /// * A `Result` indicating the success or failure of the operation.
-    pub fn write_to_json(&self, filename: &str) -> serde_json::Result<()> {
-        let graph = json!({
-            "num_of_vertices": self.num_of_vertices,
-            "num_of_edges": self.num_of_edges,
-            "neighbors": self.neighbors.iter().map(|set| set.iter().cloned().collect::<Vec<usize>>()).collect::<Vec<Vec<usize>>>(),
-        });
-        serde_json::to_writer(&File::create(filename).unwrap(), &graph)
+    /// * `Ok(())` if the file was written, `Err(GraphError::Io)` or `Err(GraphError::Json)` otherwise.
+    pub fn write_to_json(&self, filename: &str) -> Result<(), GraphError> {
+        let graph = GraphJson {
+            num_of_vertices: self.num_of_vertices,
+            num_of_edges: self.num_of_edges,
+            neighbors: self
+                .neighbors
+                .iter()
+                .map(|set| {
+                    let mut neighbors = set.iter().cloned().collect::<Vec<usize>>();
+                    neighbors.sort_unstable();
+                    neighbors
+                })
+                .collect(),
+        };
+        let file = File::create(filename)?;
+        serde_json::to_writer(file, &graph).map_err(|error| {
+            if error.is_io() {
+                GraphError::Io(error.to_string())
+            } else {
+                GraphError::Json(error.to_string())
+            }
+        })
     }
 
     /// Reads a graph from a JSON file.
@@ -379,33 +556,69 @@ impl Graph {
     /// * `filename` - The name of the file to read the graph from.
     ///
     /// # Returns
-    /// * A new instance of `Graph` read from the file.
-    pub fn read_from_json(filename: &str) -> Graph {
-        let data = std::fs::read_to_string(filename).expect("Unable to read file");
-        let json: serde_json::Value =
-            serde_json::from_str(&data).expect("JSON does not have correct format.");
-
-        let num_of_vertices = json["num_of_vertices"].as_u64().unwrap() as usize;
-        let num_of_edges = json["num_of_edges"].as_u64().unwrap() as usize;
-
-        let neighbors = json["neighbors"]
-            .as_array()
-            .unwrap()
-            .iter()
-            .map(|value| {
-                value
-                    .as_array()
-                    .unwrap()
-                    .iter()
-                    .map(|value| value.as_u64().unwrap() as usize)
-                    .collect::<HashSet<usize>>()
-            })
-            .collect::<Vec<HashSet<usize>>>();
+    /// * `Ok(Graph)` read from the file, or the `GraphError` describing why the file does not contain a valid graph.
+    pub fn read_from_json(filename: &str) -> Result<Graph, GraphError> {
+        let data = std::fs::read_to_string(filename)?;
+        let json = serde_json::from_str::<GraphJson>(&data)?;
+        Graph::try_from(json)
+    }
+}
 
-        Graph {
-            num_of_vertices,
+impl TryFrom<GraphJson> for Graph {
+    type Error = GraphError;
+
+    /// Builds a graph from its JSON representation, checking that the adjacency lists are consistent.
+    ///
+    /// # Arguments
+    /// * `json` - The deserialized graph.
+    ///
+    /// # Returns
+    /// * `Ok(Graph)` if the adjacency lists describe a simple undirected graph with the declared numbers of
+    ///   vertices and edges, or the `GraphError` pointing to the first offending vertex otherwise.
+    fn try_from(json: GraphJson) -> Result<Self, Self::Error> {
+        let n = json.num_of_vertices;
+        if json.neighbors.len() != n {
+            return Err(GraphError::VertexCountMismatch {
+                declared: n,
+                found: json.neighbors.len(),
+            });
+        }
+
+        let mut neighbors = vec![HashSet::new(); n];
+        for (vertex, list) in json.neighbors.iter().enumerate() {
+            for &neighbor in list.iter() {
+                if neighbor >= n {
+                    return Err(GraphError::NeighborOutOfRange { vertex, neighbor });
+                }
+                if neighbor == vertex {
+                    return Err(GraphError::SelfLoop(vertex));
+                }
+                if !neighbors[vertex].insert(neighbor) {
+                    return Err(GraphError::DuplicateNeighbor { vertex, neighbor });
+                }
+            }
+        }
+
+        for (vertex, set) in neighbors.iter().enumerate() {
+            let mut sorted = set.iter().cloned().collect::<Vec<usize>>();
+            sorted.sort_unstable();
+            if let Some(&neighbor) = sorted.iter().find(|&&w| !neighbors[w].contains(&vertex)) {
+                return Err(GraphError::AsymmetricNeighbor { vertex, neighbor });
+            }
+        }
+
+        let num_of_edges = neighbors.iter().map(|set| set.len()).sum::<usize>() / 2;
+        if num_of_edges != json.num_of_edges {
+            return Err(GraphError::EdgeCountMismatch {
+                declared: json.num_of_edges,
+                found: num_of_edges,
+            });
+        }
+
+        Ok(Graph {
+            num_of_vertices: n,
             num_of_edges,
             neighbors,
-        }
+        })
     }
 }
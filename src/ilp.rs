@@ -0,0 +1,319 @@
+use crate::constraints::EdgeConstraints;
+use crate::graphs::Graph;
+use crate::mps_alg::relative_gap;
+use crate::weights::EdgeWeights;
+use good_lp::solvers::highs::HighsProblem;
+use good_lp::{constraint, Expression, ProblemVariables, SolverModel, Variable};
+use highs::{HighsModelStatus, HighsSolutionStatus};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// The tolerance used when comparing weights to the dual bound of the solver and rounding the bound down.
+const BOUND_TOLERANCE: f64 = 1e-6;
+
+/// The value of the variables missing from an initial solution. Being fractional, it makes the solver leave them
+/// free when completing the solution.
+const UNKNOWN_VALUE: f64 = 0.5;
+
+/// The options of the exact MPS algorithms.
+#[derive(Clone, Debug, Default)]
+pub struct IlpOptions {
+    /// The weights of the edges, all equal to one by default.
+    pub weights: EdgeWeights,
+    /// The edges that have to be kept in or left out of the subgraph, none by default.
+    pub constraints: EdgeConstraints,
+    /// An optional planar subgraph of the input graph used as the initial solution. It is ignored unless it
+    /// satisfies the constraints.
+    pub initial: Option<Graph>,
+    /// The optional wall-clock limit for the solver.
+    pub time_limit: Option<Duration>,
+}
+
+impl IlpOptions {
+    /// Returns the initial solution if it can be used for a graph.
+    ///
+    /// # Arguments
+    /// * `g` - A reference to the input graph.
+    ///
+    /// # Returns
+    /// * `Some(&Graph)` if the initial solution is a planar subgraph of `g` containing the mandatory edges and none
+    ///   of the forbidden ones, `None` if there is no initial solution or it is invalid.
+    pub(crate) fn valid_initial(&self, g: &Graph) -> Option<&Graph> {
+        self.initial.as_ref().filter(|h| {
+            h.num_of_vertices() == g.num_of_vertices()
+                && h.all_edges()
+                    .into_iter()
+                    .all(|(u, v)| g.has_edge(u, v) && !self.constraints.is_forbidden(u, v))
+                && self
+                    .constraints
+                    .mandatory_edges()
+                    .into_iter()
+                    .all(|(u, v)| h.has_edge(u, v))
+                && h.is_planar()
+        })
+    }
+}
+
+/// The best solution of an integer program for the MPS problem found by an exact algorithm.
+#[derive(Clone, Debug)]
+pub struct IlpSolution {
+    /// The best planar subgraph found.
+    pub mps: Graph,
+    /// The total weight of the subgraph, its number of edges for unit weights.
+    pub weight: f64,
+    /// The proven upper bound on the weight of a maximum planar subgraph.
+    pub upper_bound: f64,
+    /// The relative gap between the upper bound and the weight of the found subgraph.
+    pub gap: f64,
+    /// Whether the solver was stopped by the time limit.
+    pub time_limited: bool,
+}
+
+impl IlpSolution {
+    /// Creates a solution from a planar subgraph and an upper bound on the weight of the MPS.
+    ///
+    /// # Arguments
+    /// * `g` - A reference to the input graph.
+    /// * `weights` - A reference to the weights of the edges.
+    /// * `constraints` - A reference to the mandatory and forbidden edges.
+    /// * `mps` - The best planar subgraph found.
+    /// * `dual_bound` - The upper bound reported by the solver, possibly infinite.
+    /// * `time_limited` - Whether the solver was stopped by the time limit.
+    ///
+    /// # Returns
+    /// * A new `IlpSolution` with the upper bound tightened by the trivial bound of the edges that are not forbidden
+    ///   and the corresponding gap.
+    pub(crate) fn new(
+        g: &Graph,
+        weights: &EdgeWeights,
+        constraints: &EdgeConstraints,
+        mps: Graph,
+        dual_bound: f64,
+        time_limited: bool,
+    ) -> Self {
+        let weight = weights.total_weight(&mps);
+        let mut allowed = g.clone();
+        for (u, v) in constraints.forbidden_edges() {
+            allowed.remove_edge(u, v);
+        }
+        let mut upper_bound = weights.upper_bound(&allowed);
+
+        if dual_bound.is_finite() {
+            // the weight of every subgraph is an integer, so is the weight of the optimal one
+            let dual_bound = if weights.is_integral() {
+                (dual_bound + BOUND_TOLERANCE).floor()
+            } else {
+                dual_bound
+            };
+            upper_bound = upper_bound.min(dual_bound);
+        }
+        upper_bound = upper_bound.max(weight);
+
+        IlpSolution {
+            mps,
+            weight,
+            upper_bound,
+            gap: relative_gap(weight, upper_bound),
+            time_limited,
+        }
+    }
+
+    /// Checks if the found subgraph is proven to be a maximum planar subgraph.
+    ///
+    /// # Returns
+    /// * `true` if the weight of the subgraph matches the upper bound, `false` otherwise.
+    pub fn is_optimal(&self) -> bool {
+        self.weight + BOUND_TOLERANCE >= self.upper_bound
+    }
+}
+
+/// The outcome of solving an integer program for the MPS problem.
+pub(crate) struct SolverOutcome {
+    /// The graph selected by the best solution found, empty if none was found.
+    pub mps: Graph,
+    /// The upper bound reported by the solver, possibly infinite.
+    pub dual_bound: f64,
+    /// Whether the solver was stopped by the time limit.
+    pub time_limited: bool,
+    /// Whether the solver proved that the program has no solution.
+    pub infeasible: bool,
+    /// The values of the columns in the best solution found, if any.
+    pub values: Option<Vec<f64>>,
+}
+
+/// Maps the variables of a problem to the indices of the corresponding solver columns.
+///
+/// # Arguments
+/// * `vars` - A reference to the variables of the problem.
+///
+/// # Returns
+/// * A hashmap from variables to the indices of their columns.
+pub(crate) fn column_indices(vars: &ProblemVariables) -> HashMap<Variable, usize> {
+    vars.iter_variables_with_def()
+        .enumerate()
+        .map(|(i, (var, _))| (var, i))
+        .collect()
+}
+
+/// Sums the variables selecting the given edges multiplied by the weights of the edges.
+///
+/// # Arguments
+/// * `edges` - A slice of edges represented as tuples of vertex indices.
+/// * `s` - A reference to the hashmap from edges to the variables selecting them.
+/// * `weights` - A reference to the weights of the edges.
+///
+/// # Returns
+/// * An expression representing the total weight of the selected edges.
+pub(crate) fn weighted_sum(
+    edges: &[(usize, usize)],
+    s: &HashMap<(usize, usize), Variable>,
+    weights: &EdgeWeights,
+) -> Expression {
+    edges.iter().fold(Expression::from(0), |acc, &(u, v)| {
+        acc + weights.weight(u, v) * s[&(u, v)]
+    })
+}
+
+/// Fixes the variables selecting the mandatory and the forbidden edges.
+///
+/// # Arguments
+/// * `problem` - The problem to constrain.
+/// * `s` - A reference to the hashmap from edges to the variables selecting them.
+/// * `constraints` - A reference to the mandatory and forbidden edges.
+///
+/// # Returns
+/// * The problem with `s_e = 1` for the mandatory and `s_e = 0` for the forbidden edges.
+pub(crate) fn fix_edges(
+    mut problem: HighsProblem,
+    s: &HashMap<(usize, usize), Variable>,
+    constraints: &EdgeConstraints,
+) -> HighsProblem {
+    for e in constraints.mandatory_edges() {
+        if let Some(&s_e) = s.get(&e) {
+            problem = problem.with(constraint!(s_e == 1));
+        }
+    }
+
+    for e in constraints.forbidden_edges() {
+        if let Some(&s_e) = s.get(&e) {
+            problem = problem.with(constraint!(s_e == 0));
+        }
+    }
+
+    problem
+}
+
+/// Selects the edges of a subgraph in an initial solution.
+///
+/// # Arguments
+/// * `initial_solution` - A mutable reference to the hashmap from variables to their initial values.
+/// * `s` - A reference to the hashmap from edges to the variables selecting them.
+/// * `h` - A reference to the subgraph.
+pub(crate) fn select_edges(
+    initial_solution: &mut HashMap<Variable, f64>,
+    s: &HashMap<(usize, usize), Variable>,
+    h: &Graph,
+) {
+    for (&(u, v), &s_e) in s.iter() {
+        let value = if h.has_edge(u, v) { 1.0 } else { 0.0 };
+        initial_solution.insert(s_e, value);
+    }
+}
+
+/// Solves an integer program for the MPS problem within the time limit.
+///
+/// The initial solution may be partial. The solver fixes the given values and tries to complete them to a
+/// feasible solution, which then serves as the first incumbent.
+///
+/// # Arguments
+/// * `g` - A reference to the input graph.
+/// * `weights` - A reference to the weights of the edges.
+/// * `problem` - The problem to solve.
+/// * `columns` - A reference to the hashmap from variables to the indices of their columns.
+/// * `s` - A reference to the hashmap from edges to the variables selecting them.
+/// * `initial_solution` - A reference to the hashmap from variables to their initial values, possibly empty.
+/// * `time_limit` - The optional wall-clock limit for the solver.
+///
+/// # Returns
+/// * The graph selected by the best solution found together with the values of all columns, the upper bound
+///   reported by the solver and whether the solver was stopped by the time limit or proved the program infeasible.
+///   A program without columns is solved by selecting nothing.
+pub(crate) fn solve_with_time_limit(
+    g: &Graph,
+    weights: &EdgeWeights,
+    problem: HighsProblem,
+    columns: &HashMap<Variable, usize>,
+    s: &HashMap<(usize, usize), Variable>,
+    initial_solution: &HashMap<Variable, f64>,
+    time_limit: Option<Duration>,
+) -> SolverOutcome {
+    let mut model = problem.into_inner();
+
+    if let Some(time_limit) = time_limit {
+        model.set_option("time_limit", time_limit.as_secs_f64());
+    }
+
+    if !initial_solution.is_empty() {
+        let mut values = vec![UNKNOWN_VALUE; columns.len()];
+        for (var, &value) in initial_solution.iter() {
+            values[columns[var]] = value;
+        }
+        model.set_solution(Some(&values), None, None, None);
+    }
+
+    let solved = model.solve();
+    let status = solved.status();
+    let mut selected = Graph::empty(g.num_of_vertices());
+    let mut values = None;
+
+    match status {
+        // the solver reports no solution status for a program without columns
+        HighsModelStatus::ModelEmpty => {
+            return SolverOutcome {
+                mps: selected,
+                dual_bound: 0.0,
+                time_limited: false,
+                infeasible: false,
+                values: Some(Vec::new()),
+            }
+        }
+        HighsModelStatus::Infeasible | HighsModelStatus::UnboundedOrInfeasible => {
+            return SolverOutcome {
+                mps: selected,
+                dual_bound: f64::INFINITY,
+                time_limited: false,
+                infeasible: true,
+                values,
+            }
+        }
+        _ => {}
+    }
+
+    if solved.primal_solution_status() == HighsSolutionStatus::Feasible {
+        let solution = solved.get_solution();
+        let columns_values = solution.columns();
+
+        for (&(u, v), var) in s.iter() {
+            if columns_values[columns[var]] >= 0.5 {
+                selected.add_edge(u, v);
+            }
+        }
+        values = Some(columns_values.to_vec());
+    }
+
+    let dual_bound = if status == HighsModelStatus::Optimal {
+        weights.total_weight(&selected)
+    } else {
+        solved
+            .double_info_value(c"mip_dual_bound")
+            .unwrap_or(f64::INFINITY)
+    };
+
+    SolverOutcome {
+        mps: selected,
+        dual_bound,
+        time_limited: status == HighsModelStatus::ReachedTimeLimit,
+        infeasible: false,
+        values,
+    }
+}
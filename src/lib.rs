@@ -3,6 +3,51 @@
 /// This module contains a simple graph implementation.
 pub mod graphs;
 
+/// # Edge Lists
+///
+/// This module contains readers and writers of graphs in the plain edge list and DIMACS formats.
+pub mod edge_list;
+
+/// # Nauty Formats
+///
+/// This module contains encoders and decoders of graphs in the graph6 and sparse6 formats of nauty.
+pub mod nauty;
+
+/// # GraphML and GML
+///
+/// This module contains readers and writers of graphs in the GraphML and GML formats, marking the edges of a planar subgraph.
+pub mod graphml;
+
+/// # DOT
+///
+/// This module contains a writer of graphs in the Graphviz DOT language, highlighting the edges removed by MPS algorithms.
+pub mod dot;
+
+/// # Drawing
+///
+/// This module contains straight-line layouts of planar graphs and their SVG and TikZ output.
+pub mod drawing;
+
+/// # Weights
+///
+/// This module contains edge weights for the weighted MPS problem.
+pub mod weights;
+
+/// # Constraints
+///
+/// This module contains the mandatory and forbidden edges of the constrained MPS problem.
+pub mod constraints;
+
+/// # Planarity
+///
+/// This module contains the left-right planarity test producing combinatorial planar embeddings and Kuratowski subgraphs.
+pub mod planarity;
+
+/// # Schnyder Woods
+///
+/// This module contains Schnyder woods of maximal planar graphs and the coordinates and orders they induce.
+pub mod schnyder_wood;
+
 /// # MPS Algorithm
 ///
 /// This module contains traits and interfaces for implementing Maximum Planar Subgraph (MPS) algorithms.
@@ -18,6 +63,11 @@ pub mod rand_graphs;
 /// This module contains approximation algorithms for the MPS problem using the match and merge framework.
 pub mod match_merge;
 
+/// # ILP
+///
+/// This module contains helpers for solving the integer programs of the exact MPS algorithms within a time limit.
+pub mod ilp;
+
 /// # Facial Walks
 ///
 /// This module contains an exact algorithm for the MPS problem based on facial walks.
@@ -28,6 +78,16 @@ pub mod facial_walks;
 /// This module contains an exact algorithm for the MPS problem based on the Schnyder poset characterization of planar graphs.
 pub mod schnyder;
 
+/// # Branch and Cut
+///
+/// This module contains an exact algorithm for the MPS problem that lazily adds Kuratowski subgraph constraints.
+pub mod branch_and_cut;
+
+/// # Post-processing
+///
+/// This module contains steps improving the planar subgraphs found by MPS algorithms.
+pub mod post_processing;
+
 /// # Tests
 ///
 /// This module contains tests for the library.
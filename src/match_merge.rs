@@ -1,37 +1,317 @@
+use crate::constraints::{ConstraintError, EdgeConstraints};
 use crate::graphs::Graph;
 use crate::mps_alg::*;
+use crate::weights::EdgeWeights;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::Arc;
+use std::time::Instant;
+
+/// A trait representing a rule of the Match and Merge framework.
+///
+/// A rule matches structures of the input graph whose vertices lie in different components of the subgraph and
+/// merges these components by adding the edges of the structure. To keep the subgraph planar, a rule should only add
+/// edges between different components, joining every component to the rest at a single vertex or along an edge
+/// lying on a common face, as all the built-in rules do.
+///
+/// Structures are identified by their vertices, the first of which is the vertex the search found them at.
+pub trait Rule: Send + Sync {
+    /// Returns the name of the rule.
+    ///
+    /// Rules sharing a name are searched separately, but their applications are counted together.
+    ///
+    /// # Returns
+    /// * A string slice with the name, the key of the rule in the counters of the results.
+    fn name(&self) -> &'static str;
+
+    /// Finds the structures matching the rule whose first vertex is a given one.
+    ///
+    /// Every structure found is offered to the search, which keeps the best one. The scan should stop as soon as
+    /// the search says so and offer the structures in the same order every time, e.g. by iterating the neighbors
+    /// returned by `MatchMerge::neighbors`, so that runs with the same seed give the same results.
+    ///
+    /// # Arguments
+    /// * `state` - A reference to the current state of the algorithm.
+    /// * `x` - The index of the first vertex of the structures.
+    /// * `search` - A mutable reference to the search the structures are offered to.
+    fn find(&self, state: &MatchMerge, x: usize, search: &mut RuleSearch);
+
+    /// Computes how a structure found by the rule changes the subgraph.
+    ///
+    /// # Arguments
+    /// * `state` - A reference to the current state of the algorithm.
+    /// * `structure` - A slice of the vertices of the structure, as offered by `find`.
+    ///
+    /// # Returns
+    /// * The edges to add and the vertices whose components are merged.
+    fn apply(&self, state: &MatchMerge, structure: &[usize]) -> RuleApplication;
+
+    /// Returns the vertices that may start new structures after an edge joined the subgraph.
+    ///
+    /// The structures of most rules only disappear as the input graph loses edges and components merge, so by
+    /// default no vertex has to be scanned again. Rules looking at the edges of the subgraph have to override this.
+    ///
+    /// # Arguments
+    /// * `state` - A reference to the current state of the algorithm, with the edge already added.
+    /// * `u` - The index of the first end of the edge.
+    /// * `v` - The index of the second end of the edge.
+    ///
+    /// # Returns
+    /// * A vector of vertex indices.
+    fn affected(&self, _state: &MatchMerge, _u: usize, _v: usize) -> Vec<usize> {
+        Vec::new()
+    }
+}
+
+/// A struct representing the edges added and the components merged by an application of a rule.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RuleApplication {
+    /// The edges added to the subgraph.
+    pub edges: Vec<(usize, usize)>,
+    /// The vertices whose components are merged.
+    pub vertices: Vec<usize>,
+}
+
+/// A struct representing a search for the structure a rule is applied to.
+///
+/// With unit weights the first structure offered is taken. Otherwise the search keeps the heaviest one, the first
+/// of them in case of ties.
+pub struct RuleSearch<'a> {
+    weights: Option<&'a EdgeWeights>,
+    best: Option<(f64, Vec<usize>)>,
+    matched: bool,
+    stopped: bool,
+}
+
+impl RuleSearch<'_> {
+    /// Offers a structure matching a rule to the search.
+    ///
+    /// # Arguments
+    /// * `edges` - A slice of the edges the rule would add.
+    /// * `structure` - A slice of the vertices of the structure.
+    ///
+    /// # Returns
+    /// * `true` if the search may stop at this structure, `false` if it has to look for heavier ones.
+    pub fn offer(&mut self, edges: &[(usize, usize)], structure: &[usize]) -> bool {
+        self.matched = true;
+
+        match self.weights {
+            None => {
+                self.best = Some((0.0, structure.to_vec()));
+                self.stopped = true;
+            }
+            Some(weights) => {
+                let weight = weights.sum(edges);
+                if self.best.as_ref().is_none_or(|&(b, _)| weight > b) {
+                    self.best = Some((weight, structure.to_vec()));
+                }
+            }
+        }
+
+        self.stopped
+    }
+}
+
+/// Identifies a rule by the address of its shared instance, so rules with equal names keep separate queues.
+///
+/// # Arguments
+/// * `rule` - A reference to the rule.
+///
+/// # Returns
+/// * The address of the rule.
+fn rule_id(rule: &Arc<dyn Rule>) -> usize {
+    Arc::as_ptr(rule) as *const () as usize
+}
 
-/// A struct representing the Match and Merge algorithm for finding the maximum planar subgraph.
-struct MatchMerge {
+/// A struct representing the pending vertices of a rule, stored as their positions in the search order.
+struct RuleQueue {
+    rule: Arc<dyn Rule>,
+    pending: BTreeSet<usize>,
+}
+
+/// A struct representing the state of the Match and Merge algorithm for finding the maximum planar subgraph.
+///
+/// The components are kept in a disjoint-set forest. Every rule keeps a queue of the vertices that may still start
+/// a structure matching it, so a search resumes where the previous one stopped instead of rescanning all vertices.
+/// The vertices are searched in the order of their indices unless the order is shuffled.
+pub struct MatchMerge {
     n: usize,
     full: Graph,
     mps: Graph,
-    component: Vec<usize>,
-    components: Vec<Vec<usize>>,
+    parent: Vec<usize>,
+    size: Vec<usize>,
+    order: Vec<usize>,
+    position: Vec<usize>,
+    queues: HashMap<usize, RuleQueue>,
+    applications: BTreeMap<&'static str, usize>,
+    weights: Option<EdgeWeights>,
 }
 
 impl MatchMerge {
     /// Creates a new MatchMerge instance.
     ///
+    /// With unit weights the rules are applied to the first structures found. Otherwise every search picks the
+    /// heaviest structure, so heavy triangles and D4 structures are merged first as in the weighted Calinescu
+    /// algorithm.
+    ///
+    /// The forbidden edges are never considered and the mandatory edges are added up front, merging the components
+    /// they connect. Every rule joins different components, each at a single vertex or an edge, so the subgraph
+    /// stays planar.
+    ///
     /// # Arguments
     /// * `g` - A reference to the input graph.
+    /// * `weights` - A reference to the weights of the edges.
+    /// * `constraints` - A reference to the mandatory and forbidden edges.
     ///
     /// # Returns
-    /// * A new instance of `MatchMerge`.
-    pub fn new(g: &Graph) -> MatchMerge {
+    /// * `Ok(MatchMerge)` with the mandatory edges applied, or the `ConstraintError` if they cannot be satisfied.
+    pub(crate) fn new(
+        g: &Graph,
+        weights: &EdgeWeights,
+        constraints: &EdgeConstraints,
+    ) -> Result<MatchMerge, ConstraintError> {
         let n = g.num_of_vertices();
-        let full = g.clone();
-        let mps = Graph::empty(n);
-        let component = (0..n).collect::<Vec<usize>>();
-        let components = (0..n).map(|x| vec![x]).collect::<Vec<Vec<usize>>>();
+        let mps = constraints.validate(g)?;
+        let mut full = g.clone();
+
+        for (u, v) in constraints.forbidden_edges() {
+            if v < n {
+                full.remove_edge(u, v);
+            }
+        }
 
-        MatchMerge {
+        let mut mm = MatchMerge {
             n,
             full,
-            mps,
-            component,
-            components,
+            mps: Graph::empty(n),
+            parent: (0..n).collect(),
+            size: vec![1; n],
+            order: (0..n).collect(),
+            position: (0..n).collect(),
+            queues: HashMap::new(),
+            applications: BTreeMap::new(),
+            weights: (!weights.is_unit()).then(|| weights.clone()),
+        };
+
+        for (u, v) in mps.all_edges() {
+            mm.mps.add_edge(u, v);
+            mm.full.remove_edge(u, v);
+            mm.connect_components(&[u, v]);
         }
+
+        Ok(mm)
+    }
+
+    /// Returns the remaining edges of the input graph, i.e. those neither added to the subgraph nor forbidden.
+    ///
+    /// # Returns
+    /// * A reference to the graph of the remaining edges.
+    pub fn full(&self) -> &Graph {
+        &self.full
+    }
+
+    /// Returns the planar subgraph built so far.
+    ///
+    /// # Returns
+    /// * A reference to the subgraph.
+    pub fn mps(&self) -> &Graph {
+        &self.mps
+    }
+
+    /// Returns the remaining neighbors of a vertex in the search order.
+    ///
+    /// Unlike the adjacency sets of the graph, whose iteration order differs between processes, this order only
+    /// depends on the seed of the search.
+    ///
+    /// # Arguments
+    /// * `v` - The index of the vertex.
+    ///
+    /// # Returns
+    /// * A vector of the indices of the neighbors of `v` in the input graph without the edges already added.
+    pub fn neighbors(&self, v: usize) -> Vec<usize> {
+        let mut neighbors = self
+            .full
+            .neighbors(v)
+            .unwrap()
+            .iter()
+            .copied()
+            .collect::<Vec<usize>>();
+        neighbors.sort_unstable_by_key(|&u| self.position[u]);
+        neighbors
+    }
+
+    /// Shuffles the order in which the vertices are searched.
+    ///
+    /// # Arguments
+    /// * `seed` - The seed of the random number generator, the same seed giving the same order.
+    fn shuffle_order(&mut self, seed: u64) {
+        fastrand::Rng::with_seed(seed).shuffle(&mut self.order);
+
+        for (i, &v) in self.order.iter().enumerate() {
+            self.position[v] = i;
+        }
+    }
+
+    /// Searches for a structure matching a rule among the vertices in the queue of the rule.
+    ///
+    /// The vertices are scanned in the search order and those starting no structure leave the queue. Structures
+    /// only disappear as edges leave the input graph and components merge, so these vertices are not scanned again
+    /// unless `apply_rule` puts them back. The search thus finds the same structure as a scan of all vertices.
+    ///
+    /// # Arguments
+    /// * `rule` - A reference to the rule, whose instance identifies its queue.
+    ///
+    /// # Returns
+    /// * The first structure found with unit weights or the heaviest one otherwise, `None` if there is none.
+    fn search(&mut self, rule: &Arc<dyn Rule>) -> Option<Vec<usize>> {
+        let mut queue = self
+            .queues
+            .remove(&rule_id(rule))
+            .unwrap_or_else(|| RuleQueue {
+                rule: rule.clone(),
+                pending: (0..self.n).collect(),
+            });
+        let mut search = RuleSearch {
+            weights: self.weights.as_ref(),
+            best: None,
+            matched: false,
+            stopped: false,
+        };
+        let mut exhausted = Vec::new();
+
+        for &i in queue.pending.iter() {
+            search.matched = false;
+            rule.find(self, self.order[i], &mut search);
+
+            if search.stopped {
+                break;
+            } else if !search.matched {
+                exhausted.push(i);
+            }
+        }
+
+        let best = search.best.map(|(_, structure)| structure);
+        for i in exhausted {
+            queue.pending.remove(&i);
+        }
+        self.queues.insert(rule_id(rule), queue);
+
+        best
+    }
+
+    /// Finds the representative of the component containing a vertex.
+    ///
+    /// Smaller components are always attached to larger ones, so the paths to the representatives are short.
+    ///
+    /// # Arguments
+    /// * `v` - The index of the vertex.
+    ///
+    /// # Returns
+    /// * The index of the representative, shared by all vertices of the component.
+    pub fn component(&self, mut v: usize) -> usize {
+        while self.parent[v] != v {
+            v = self.parent[v];
+        }
+        v
     }
 
     /// Checks if all vertices belong to different components.
@@ -41,12 +321,16 @@ impl MatchMerge {
     ///
     /// # Returns
     /// * `true` if all vertices are in different components, `false` otherwise.
-    fn all_different_components(&self, vertices: &[usize]) -> bool {
-        let n = vertices.len();
+    pub fn all_different_components(&self, vertices: &[usize]) -> bool {
+        let components = vertices
+            .iter()
+            .map(|&v| self.component(v))
+            .collect::<Vec<usize>>();
+        let n = components.len();
 
         for i in 0..n {
             for j in i + 1..n {
-                if self.component[vertices[i]] == self.component[vertices[j]] {
+                if components[i] == components[j] {
                     return false;
                 }
             }
@@ -60,240 +344,877 @@ impl MatchMerge {
     /// # Arguments
     /// * `vs` - A slice of vertex indices.
     fn connect_components(&mut self, vs: &[usize]) {
-        if vs.len() <= 1 {
-            return;
-        }
-
-        let n = vs.len();
-        let u = vs[0];
-        let u_comp = self.component[u];
-
-        for v in 1..n {
-            let v_comp = self.component[vs[v]];
+        for &v in vs.iter().skip(1) {
+            let (mut u, mut v) = (self.component(vs[0]), self.component(v));
 
-            if v_comp == u_comp {
+            if u == v {
                 continue;
             }
 
-            while let Some(x) = self.components[v_comp].pop() {
-                self.component[x] = u_comp;
-                self.components[u_comp].push(x);
+            if self.size[u] < self.size[v] {
+                std::mem::swap(&mut u, &mut v);
             }
+            self.parent[v] = u;
+            self.size[u] += self.size[v];
         }
     }
 
+    /// Labels the vertices by their components.
+    ///
+    /// # Returns
+    /// * A vector with the index of the component of every vertex, the components numbered from 0 in the order of
+    ///   their smallest vertices.
+    fn component_labels(&self) -> Vec<usize> {
+        let mut labels = vec![usize::MAX; self.n];
+        let mut next = 0;
+
+        for v in 0..self.n {
+            let root = self.component(v);
+            if labels[root] == usize::MAX {
+                labels[root] = next;
+                next += 1;
+            }
+        }
+
+        (0..self.n).map(|v| labels[self.component(v)]).collect()
+    }
+
     /// Applies a given rule by adding edges and connecting components.
     ///
+    /// The vertices that may start new structures of some rule after an edge joined the subgraph return to the
+    /// queue of the rule.
+    ///
     /// # Arguments
-    /// * `edges` - A slice of edge tuples to be added to the MPS.
-    /// * `vertices` - A slice of vertex indices involved in the rule.
-    fn apply_rule(&mut self, edges: &[(usize, usize)], vertices: &[usize]) {
-        for &(u, v) in edges {
+    /// * `rule` - The name of the rule, used to count its applications.
+    /// * `application` - A reference to the edges to add and the vertices whose components are merged.
+    fn apply_rule(&mut self, rule: &'static str, application: &RuleApplication) {
+        for &(u, v) in application.edges.iter() {
             self.mps.add_edge(u, v);
             self.full.remove_edge(u, v);
+
+            let affected = self
+                .queues
+                .iter()
+                .map(|(&id, queue)| (id, queue.rule.affected(self, u, v)))
+                .collect::<Vec<_>>();
+            for (id, vertices) in affected {
+                if let Some(queue) = self.queues.get_mut(&id) {
+                    queue
+                        .pending
+                        .extend(vertices.into_iter().map(|v| self.position[v]));
+                }
+            }
         }
 
-        self.connect_components(vertices);
+        self.connect_components(&application.vertices);
+        *self.applications.entry(rule).or_insert(0) += 1;
     }
 
-    /// Finds a set of vertices that match the custom rule.
+    /// Finishes the algorithm and reports the subgraph together with the rule applications.
+    ///
+    /// # Arguments
+    /// * `g` - A reference to the input graph.
+    /// * `weights` - A reference to the weights of the edges.
+    /// * `start` - The instant at which the algorithm started.
     ///
     /// # Returns
-    /// * An optional tuple of five vertex indices if the rule is matched, or `None` otherwise.
-    fn find_my_rule(&self) -> Option<(usize, usize, usize, usize, usize)> {
-        for x in 0..self.n {
-            let x_neighbors = self.full.neighbors(x).unwrap();
-            for &y in x_neighbors {
-                for &z in x_neighbors {
-                    if y == z || !self.full.has_edge(y, z) {
-                        continue;
-                    }
-
-                    for &w in x_neighbors {
-                        if w == y || w == z || !self.full.has_edge(z, w) {
-                            continue;
-                        }
-
-                        for &t in x_neighbors {
-                            if t == y || t == z || t == w || !self.full.has_edge(w, t) {
-                                continue;
-                            }
+    /// * The result of the heuristic.
+    fn into_result(self, g: &Graph, weights: &EdgeWeights, start: Instant) -> MpsResult {
+        MpsResult::heuristic(g, weights, self.mps, start.elapsed()).with_counters(self.applications)
+    }
 
-                            if !self.all_different_components(&[x, y, z, w, t]) {
-                                continue;
-                            }
+    /// Applies a rule to a single structure, if one matches it.
+    ///
+    /// # Arguments
+    /// * `rule` - A reference to the rule to apply.
+    ///
+    /// # Returns
+    /// * `true` if a structure matched the rule, `false` otherwise.
+    fn apply_once(&mut self, rule: &Arc<dyn Rule>) -> bool {
+        match self.search(rule) {
+            Some(structure) => {
+                let application = rule.apply(self, &structure);
+                self.apply_rule(rule.name(), &application);
+                true
+            }
+            None => false,
+        }
+    }
 
-                            return Some((x, y, z, w, t));
-                        }
+    /// Runs a phase of a pipeline.
+    ///
+    /// # Arguments
+    /// * `phase` - A reference to the phase.
+    fn run_phase(&mut self, phase: &MatchMergePhase) {
+        loop {
+            let mut applied = false;
+
+            for step in phase.steps.iter() {
+                if step.exhaustive {
+                    while self.apply_once(&step.rule) {
+                        applied = true;
                     }
+                } else if self.apply_once(&step.rule) {
+                    applied = true;
                 }
             }
+
+            if !phase.repeat || !applied {
+                break;
+            }
         }
+    }
+}
+
+/// A struct representing the rule merging the components of a vertex and of a path in its neighborhood.
+///
+/// A fan of length `k` consists of a vertex adjacent to all vertices of a path on `k` vertices. It merges `k + 1`
+/// components with `2k - 1` edges. The fans of length 4 form the custom rule, the fans of length 3 the D4 rule.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FanRule {
+    length: usize,
+    name: &'static str,
+}
 
-        None
+impl FanRule {
+    /// Creates a fan rule.
+    ///
+    /// # Arguments
+    /// * `length` - The number of vertices of the path, at least one.
+    /// * `name` - The name of the rule.
+    ///
+    /// # Returns
+    /// * A new instance of `FanRule`.
+    ///
+    /// # Panics
+    /// * If the length is zero.
+    pub const fn new(length: usize, name: &'static str) -> Self {
+        assert!(length > 0, "a fan contains at least one edge");
+        FanRule { length, name }
     }
 
-    /// Applies the custom rule by adding edges and connecting components.
+    /// Returns the number of vertices of the path.
+    ///
+    /// # Returns
+    /// * The length of the fans.
+    pub fn length(&self) -> usize {
+        self.length
+    }
+
+    /// Lists the edges of a fan, the edges from the center first and then the edges of the path.
     ///
     /// # Arguments
-    /// * `x` - Vertex index.
-    /// * `y` - Vertex index.
-    /// * `z` - Vertex index.
-    /// * `w` - Vertex index.
-    /// * `t` - Vertex index.
-    fn apply_my_rule(&mut self, x: usize, y: usize, z: usize, w: usize, t: usize) {
-        self.apply_rule(
-            &[(x, y), (x, z), (x, w), (x, t), (y, z), (z, w), (w, t)],
-            &[x, y, z, w, t],
-        );
+    /// * `structure` - A slice of the center followed by the vertices of the path.
+    ///
+    /// # Returns
+    /// * A vector of `2k - 1` edges.
+    fn edges(structure: &[usize]) -> Vec<(usize, usize)> {
+        let x = structure[0];
+        let path = &structure[1..];
+
+        path.iter()
+            .map(|&y| (x, y))
+            .chain(path.windows(2).map(|pair| (pair[0], pair[1])))
+            .collect()
     }
 
-    /// Finds a set of vertices that match the D4 rule.
+    /// Extends the paths of fans in the neighborhood of the center in all possible ways.
+    ///
+    /// # Arguments
+    /// * `state` - A reference to the current state of the algorithm.
+    /// * `x_neighbors` - A reference to the neighbors of the center.
+    /// * `structure` - A mutable reference to the center followed by the path built so far.
+    /// * `search` - A mutable reference to the search the fans are offered to.
     ///
     /// # Returns
-    /// * An optional tuple of four vertex indices if the rule is matched, or `None` otherwise.
-    fn find_d4_rule(&self) -> Option<(usize, usize, usize, usize)> {
-        for x in 0..self.n {
-            let x_neighbors = self.full.neighbors(x).unwrap();
-            for &y in x_neighbors {
-                for &z in x_neighbors {
-                    for &w in x_neighbors {
-                        if y == z || y == w || z == w {
-                            continue;
-                        }
+    /// * `true` if the search may stop, `false` otherwise.
+    fn extend(
+        &self,
+        state: &MatchMerge,
+        x_neighbors: &[usize],
+        structure: &mut Vec<usize>,
+        search: &mut RuleSearch,
+    ) -> bool {
+        if structure.len() == self.length + 1 {
+            return search.offer(&FanRule::edges(structure), structure);
+        }
 
-                        if !self.full.has_edge(y, z) || !self.full.has_edge(z, w) {
-                            continue;
-                        }
+        for &y in x_neighbors {
+            let last = structure[structure.len() - 1];
+            if structure.len() > 1 && !state.full.has_edge(last, y) {
+                continue;
+            }
 
-                        if !self.all_different_components(&[x, y, z, w]) {
-                            continue;
-                        }
+            structure.push(y);
+            let stop = state.all_different_components(structure)
+                && self.extend(state, x_neighbors, structure, search);
+            structure.pop();
 
-                        return Some((x, y, z, w));
-                    }
-                }
+            if stop {
+                return true;
             }
         }
 
-        None
+        false
+    }
+}
+
+impl Rule for FanRule {
+    /// Returns the name of the rule.
+    ///
+    /// # Returns
+    /// * A string slice with the name given to the rule.
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Finds the fans centered at a given vertex, extending the paths in the order of the neighbors of the center.
+    ///
+    /// # Arguments
+    /// * `state` - A reference to the current state of the algorithm.
+    /// * `x` - The index of the center.
+    /// * `search` - A mutable reference to the search the fans are offered to.
+    fn find(&self, state: &MatchMerge, x: usize, search: &mut RuleSearch) {
+        let x_neighbors = state.neighbors(x);
+        self.extend(state, &x_neighbors, &mut vec![x], search);
     }
 
-    /// Applies the D4 rule by adding edges and connecting components.
+    /// Adds the edges of a fan and merges the components of its vertices.
     ///
     /// # Arguments
-    /// * `x` - Vertex index.
-    /// * `y` - Vertex index.
-    /// * `z` - Vertex index.
-    /// * `w` - Vertex index.
-    fn apply_d4_rule(&mut self, x: usize, y: usize, z: usize, w: usize) {
-        self.apply_rule(&[(x, y), (x, z), (x, w), (y, z), (w, z)], &[x, y, z, w]);
+    /// * `_state` - A reference to the current state of the algorithm.
+    /// * `structure` - A slice of the center followed by the vertices of the path.
+    ///
+    /// # Returns
+    /// * The `2k - 1` edges of the fan and its `k + 1` vertices.
+    fn apply(&self, _state: &MatchMerge, structure: &[usize]) -> RuleApplication {
+        RuleApplication {
+            edges: FanRule::edges(structure),
+            vertices: structure.to_vec(),
+        }
     }
+}
+
+/// A struct representing the rule merging the components along the longest fan found greedily around a vertex.
+///
+/// Starting from every neighbor of the center, the path is extended by the first common neighbor of the center and
+/// the last vertex of the path lying in a new component, until no such vertex exists or the path reaches the maximum
+/// length. The longest of these fans is offered, provided its path has at least two vertices. A fan of length `k`
+/// adds `2k - 1` edges and merges `k + 1` components, so longer fans approach two edges per merged component.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LongestFanRule {
+    max_length: usize,
+}
 
-    /// Finds a set of vertices that match the K3 rule.
+impl LongestFanRule {
+    /// Creates a longest fan rule.
+    ///
+    /// # Arguments
+    /// * `max_length` - The maximum number of vertices of the path, at least two.
     ///
     /// # Returns
-    /// * An optional tuple of three vertex indices if the rule is matched, or `None` otherwise.
-    fn find_k3_rule(&self) -> Option<(usize, usize, usize)> {
-        for x in 0..self.n {
-            let x_neighbors = self.full.neighbors(x).unwrap();
-            for &y in x_neighbors {
-                for &z in x_neighbors {
-                    if y == z {
-                        continue;
-                    }
+    /// * A new instance of `LongestFanRule`.
+    ///
+    /// # Panics
+    /// * If the maximum length is smaller than two.
+    pub fn new(max_length: usize) -> Self {
+        assert!(max_length >= 2, "a fan contains at least a triangle");
+        LongestFanRule { max_length }
+    }
 
-                    if !self.full.has_edge(y, z) {
-                        continue;
-                    }
+    /// Returns the maximum number of vertices of the path.
+    ///
+    /// # Returns
+    /// * The maximum length of the fans.
+    pub fn max_length(&self) -> usize {
+        self.max_length
+    }
 
-                    if !self.all_different_components(&[x, y, z]) {
-                        continue;
-                    }
+    /// Extends the path of a fan greedily.
+    ///
+    /// # Arguments
+    /// * `state` - A reference to the current state of the algorithm.
+    /// * `structure` - A mutable reference to the center followed by the path, extended in place.
+    fn extend(&self, state: &MatchMerge, structure: &mut Vec<usize>) {
+        let x = structure[0];
+
+        while structure.len() <= self.max_length {
+            let last = structure[structure.len() - 1];
+            let next = state.neighbors(last).into_iter().find(|&z| {
+                structure.push(z);
+                let valid = state.full.has_edge(x, z) && state.all_different_components(structure);
+                structure.pop();
+                valid
+            });
+
+            match next {
+                Some(z) => structure.push(z),
+                None => break,
+            }
+        }
+    }
+}
 
-                    return Some((x, y, z));
+impl Rule for LongestFanRule {
+    /// Returns the name of the rule.
+    ///
+    /// # Returns
+    /// * The string slice `Fan`.
+    fn name(&self) -> &'static str {
+        "Fan"
+    }
+
+    /// Finds the longest fan centered at a given vertex among those built greedily from each of its neighbors.
+    ///
+    /// # Arguments
+    /// * `state` - A reference to the current state of the algorithm.
+    /// * `x` - The index of the center.
+    /// * `search` - A mutable reference to the search the fan is offered to.
+    fn find(&self, state: &MatchMerge, x: usize, search: &mut RuleSearch) {
+        let mut longest = Vec::new();
+
+        for y in state.neighbors(x) {
+            if !state.all_different_components(&[x, y]) {
+                continue;
+            }
+
+            let mut structure = vec![x, y];
+            self.extend(state, &mut structure);
+
+            if structure.len() > longest.len() {
+                longest = structure;
+                if longest.len() > self.max_length {
+                    break;
                 }
             }
         }
 
-        None
+        if longest.len() >= 3 {
+            search.offer(&FanRule::edges(&longest), &longest);
+        }
     }
 
-    /// Applies the K3 rule by adding edges and connecting components.
+    /// Adds the edges of a fan and merges the components of its vertices.
     ///
     /// # Arguments
-    /// * `u` - Vertex index.
-    /// * `v` - Vertex index.
-    /// * `w` - Vertex index.
-    fn apply_k3_rule(&mut self, u: usize, v: usize, w: usize) {
-        self.apply_rule(&[(u, v), (v, w), (w, u)], &[u, v, w]);
+    /// * `_state` - A reference to the current state of the algorithm.
+    /// * `structure` - A slice of the center followed by the vertices of the path.
+    ///
+    /// # Returns
+    /// * The `2k - 1` edges of the fan and its `k + 1` vertices.
+    fn apply(&self, _state: &MatchMerge, structure: &[usize]) -> RuleApplication {
+        RuleApplication {
+            edges: FanRule::edges(structure),
+            vertices: structure.to_vec(),
+        }
     }
+}
 
-    /// Finds a set of vertices that match the Poranen rule.
+/// A struct representing the rule merging the components of the vertices of a triangle.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct K3Rule;
+
+impl Rule for K3Rule {
+    /// Returns the name of the rule.
     ///
     /// # Returns
-    /// * An optional tuple of three vertex indices if the rule is matched, or `None` otherwise.
-    fn find_poranen_rule(&self) -> Option<(usize, usize, usize)> {
-        for x in 0..self.n {
-            let x_neighbors = self.full.neighbors(x).unwrap();
-            for &y in x_neighbors {
-                for &z in x_neighbors {
-                    if y == z {
-                        continue;
-                    }
+    /// * The string slice `K3`.
+    fn name(&self) -> &'static str {
+        "K3"
+    }
 
-                    if !self.mps.has_edge(y, z) {
-                        continue;
-                    }
+    /// Finds the triangles containing a given vertex.
+    ///
+    /// # Arguments
+    /// * `state` - A reference to the current state of the algorithm.
+    /// * `x` - The index of the vertex.
+    /// * `search` - A mutable reference to the search the triangles are offered to.
+    fn find(&self, state: &MatchMerge, x: usize, search: &mut RuleSearch) {
+        let x_neighbors = state.neighbors(x);
+
+        for &y in &x_neighbors {
+            if !state.all_different_components(&[x, y]) {
+                continue;
+            }
 
-                    if !self.all_different_components(&[x, y]) {
-                        continue;
-                    }
+            for &z in &x_neighbors {
+                if !state.full.has_edge(y, z) || !state.all_different_components(&[x, y, z]) {
+                    continue;
+                }
 
-                    return Some((x, y, z));
+                if search.offer(&[(x, y), (y, z), (z, x)], &[x, y, z]) {
+                    return;
                 }
             }
         }
-
-        None
     }
 
-    /// Applies the Poranen rule by adding edges and connecting components.
+    /// Adds the edges of a triangle and merges the components of its vertices.
     ///
     /// # Arguments
-    /// * `u` - Vertex index.
-    /// * `v` - Vertex index.
-    /// * `w` - Vertex index.
-    fn apply_poranen_rule(&mut self, u: usize, v: usize, w: usize) {
-        self.apply_rule(&[(u, v), (u, w)], &[u, v, w]);
+    /// * `_state` - A reference to the current state of the algorithm.
+    /// * `structure` - A slice of the three vertices of the triangle.
+    ///
+    /// # Returns
+    /// * The three edges and vertices of the triangle.
+    fn apply(&self, _state: &MatchMerge, structure: &[usize]) -> RuleApplication {
+        let (u, v, w) = (structure[0], structure[1], structure[2]);
+        RuleApplication {
+            edges: vec![(u, v), (v, w), (w, u)],
+            vertices: vec![u, v, w],
+        }
     }
+}
 
-    /// Finds a set of vertices that match the K2 rule.
+/// A struct representing the rule attaching a vertex to an edge of the subgraph, forming a triangle.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PoranenRule;
+
+impl Rule for PoranenRule {
+    /// Returns the name of the rule.
     ///
     /// # Returns
-    /// * An optional tuple of two vertex indices if the rule is matched, or `None` otherwise.
-    fn find_k2_rule(&self) -> Option<(usize, usize)> {
-        for x in 0..self.n {
-            let x_neighbors = self.full.neighbors(x).unwrap();
-            for &y in x_neighbors {
-                if !self.all_different_components(&[x, y]) {
+    /// * The string slice `Poranen`.
+    fn name(&self) -> &'static str {
+        "Poranen"
+    }
+
+    /// Finds the edges of the subgraph in the neighborhood of a given vertex from another component.
+    ///
+    /// # Arguments
+    /// * `state` - A reference to the current state of the algorithm.
+    /// * `x` - The index of the vertex.
+    /// * `search` - A mutable reference to the search the structures are offered to.
+    fn find(&self, state: &MatchMerge, x: usize, search: &mut RuleSearch) {
+        let x_neighbors = state.neighbors(x);
+
+        for &y in &x_neighbors {
+            if !state.all_different_components(&[x, y]) {
+                continue;
+            }
+
+            for &z in &x_neighbors {
+                if !state.mps.has_edge(y, z) {
                     continue;
                 }
 
-                return Some((x, y));
+                if search.offer(&[(x, y), (x, z)], &[x, y, z]) {
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Connects a vertex to both ends of an edge of the subgraph.
+    ///
+    /// # Arguments
+    /// * `_state` - A reference to the current state of the algorithm.
+    /// * `structure` - A slice of the vertex followed by the ends of the edge.
+    ///
+    /// # Returns
+    /// * The two new edges of the triangle and its three vertices.
+    fn apply(&self, _state: &MatchMerge, structure: &[usize]) -> RuleApplication {
+        let (u, v, w) = (structure[0], structure[1], structure[2]);
+        RuleApplication {
+            edges: vec![(u, v), (u, w)],
+            vertices: vec![u, v, w],
+        }
+    }
+
+    /// Returns the common neighbors of the ends of an edge, as the edge may form a triangle with any of them.
+    ///
+    /// # Arguments
+    /// * `state` - A reference to the current state of the algorithm, with the edge already added.
+    /// * `u` - The index of the first end of the edge.
+    /// * `v` - The index of the second end of the edge.
+    ///
+    /// # Returns
+    /// * A vector of the common neighbors in the remaining input graph.
+    fn affected(&self, state: &MatchMerge, u: usize, v: usize) -> Vec<usize> {
+        let u_neighbors = state.full.neighbors(u).unwrap();
+        let v_neighbors = state.full.neighbors(v).unwrap();
+        u_neighbors.intersection(v_neighbors).copied().collect()
+    }
+}
+
+/// A struct representing the rule merging the components of the ends of an edge.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct K2Rule;
+
+impl Rule for K2Rule {
+    /// Returns the name of the rule.
+    ///
+    /// # Returns
+    /// * The string slice `K2`.
+    fn name(&self) -> &'static str {
+        "K2"
+    }
+
+    /// Finds the edges between a given vertex and other components.
+    ///
+    /// # Arguments
+    /// * `state` - A reference to the current state of the algorithm.
+    /// * `x` - The index of the vertex.
+    /// * `search` - A mutable reference to the search the edges are offered to.
+    fn find(&self, state: &MatchMerge, x: usize, search: &mut RuleSearch) {
+        for y in state.neighbors(x) {
+            if !state.all_different_components(&[x, y]) {
+                continue;
+            }
+
+            if search.offer(&[(x, y)], &[x, y]) {
+                return;
             }
         }
+    }
+
+    /// Adds an edge and merges the components of its ends.
+    ///
+    /// # Arguments
+    /// * `_state` - A reference to the current state of the algorithm.
+    /// * `structure` - A slice of the two ends of the edge.
+    ///
+    /// # Returns
+    /// * The edge and its ends.
+    fn apply(&self, _state: &MatchMerge, structure: &[usize]) -> RuleApplication {
+        RuleApplication {
+            edges: vec![(structure[0], structure[1])],
+            vertices: structure.to_vec(),
+        }
+    }
+}
+
+/// The custom rule, merging five components along a fan of length 4.
+const MY_RULE: FanRule = FanRule::new(4, "My");
+
+/// The D4 rule, merging four components along a fan of length 3.
+const D4_RULE: FanRule = FanRule::new(3, "D4");
+
+/// An enum representing the built-in rules of the Match and Merge framework.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MatchMergeRule {
+    /// Merges five components along a fan of a vertex with four neighbors forming a path, adding seven edges.
+    My,
+    /// Merges four components along a vertex with three neighbors forming a path, adding five edges.
+    D4,
+    /// Merges three components along a triangle, adding three edges.
+    K3,
+    /// Merges the component of a vertex with an edge of the subgraph forming a triangle with it, adding two edges.
+    Poranen,
+    /// Merges two components along an edge.
+    K2,
+}
+
+impl MatchMergeRule {
+    /// Returns the implementation of the rule.
+    ///
+    /// # Returns
+    /// * A reference to the rule.
+    fn rule(&self) -> &'static dyn Rule {
+        match self {
+            MatchMergeRule::My => &MY_RULE,
+            MatchMergeRule::D4 => &D4_RULE,
+            MatchMergeRule::K3 => &K3Rule,
+            MatchMergeRule::Poranen => &PoranenRule,
+            MatchMergeRule::K2 => &K2Rule,
+        }
+    }
+}
+
+impl Rule for MatchMergeRule {
+    /// Returns the name of the rule.
+    ///
+    /// # Returns
+    /// * A string slice with the name, the key of the rule in the counters of the results.
+    fn name(&self) -> &'static str {
+        self.rule().name()
+    }
+
+    /// Finds the structures matching the rule whose first vertex is a given one.
+    ///
+    /// # Arguments
+    /// * `state` - A reference to the current state of the algorithm.
+    /// * `x` - The index of the first vertex of the structures.
+    /// * `search` - A mutable reference to the search the structures are offered to.
+    fn find(&self, state: &MatchMerge, x: usize, search: &mut RuleSearch) {
+        self.rule().find(state, x, search)
+    }
+
+    /// Computes how a structure found by the rule changes the subgraph.
+    ///
+    /// # Arguments
+    /// * `state` - A reference to the current state of the algorithm.
+    /// * `structure` - A slice of the vertices of the structure.
+    ///
+    /// # Returns
+    /// * The edges to add and the vertices whose components are merged.
+    fn apply(&self, state: &MatchMerge, structure: &[usize]) -> RuleApplication {
+        self.rule().apply(state, structure)
+    }
+
+    /// Returns the vertices that may start new structures after an edge joined the subgraph.
+    ///
+    /// # Arguments
+    /// * `state` - A reference to the current state of the algorithm.
+    /// * `u` - The index of the first end of the edge.
+    /// * `v` - The index of the second end of the edge.
+    ///
+    /// # Returns
+    /// * A vector of vertex indices.
+    fn affected(&self, state: &MatchMerge, u: usize, v: usize) -> Vec<usize> {
+        self.rule().affected(state, u, v)
+    }
+}
+
+/// A struct representing a step of a phase, applying a rule once or as long as it matches.
+#[derive(Clone)]
+pub struct RuleStep {
+    /// The applied rule.
+    pub rule: Arc<dyn Rule>,
+    /// Whether the rule is applied until no structure matches it, instead of at most once.
+    pub exhaustive: bool,
+}
+
+impl RuleStep {
+    /// Creates a step applying a rule until no structure matches it.
+    ///
+    /// # Arguments
+    /// * `rule` - The applied rule.
+    ///
+    /// # Returns
+    /// * A new instance of `RuleStep`.
+    pub fn exhaustive<R: Rule + 'static>(rule: R) -> Self {
+        RuleStep {
+            rule: Arc::new(rule),
+            exhaustive: true,
+        }
+    }
+
+    /// Creates a step applying a rule to at most one structure.
+    ///
+    /// # Arguments
+    /// * `rule` - The applied rule.
+    ///
+    /// # Returns
+    /// * A new instance of `RuleStep`.
+    pub fn once<R: Rule + 'static>(rule: R) -> Self {
+        RuleStep {
+            rule: Arc::new(rule),
+            exhaustive: false,
+        }
+    }
+}
+
+/// A struct representing a phase of a pipeline, a sequence of steps run once or until none of them applies a rule.
+#[derive(Clone)]
+pub struct MatchMergePhase {
+    /// The steps in the order they are run.
+    pub steps: Vec<RuleStep>,
+    /// Whether the steps are run again as long as some of them applied a rule.
+    pub repeat: bool,
+}
+
+/// A struct building Match and Merge algorithms from phases.
+///
+/// The phases are run in the order they are added. The components are reported as they are at the point marked by
+/// `capture_components`, or at the end. Pipelines should usually end with the K2 rule, otherwise the subgraph may be
+/// disconnected, and capture the components just before it. The Poranen algorithm, for example, repeats the
+/// exhaustive Poranen rule followed by a single K3 rule and then applies the K2 rule.
+#[derive(Clone)]
+pub struct MatchMergeBuilder {
+    name: &'static str,
+    phases: Vec<MatchMergePhase>,
+    capture: Option<usize>,
+    seed: Option<u64>,
+}
+
+impl MatchMergeBuilder {
+    /// Creates a builder of an algorithm without phases.
+    ///
+    /// # Arguments
+    /// * `name` - The name of the built algorithm.
+    ///
+    /// # Returns
+    /// * A new instance of `MatchMergeBuilder`.
+    pub fn new(name: &'static str) -> Self {
+        MatchMergeBuilder {
+            name,
+            phases: Vec::new(),
+            capture: None,
+            seed: None,
+        }
+    }
+
+    /// Adds a phase applying a rule until no structure matches it.
+    ///
+    /// # Arguments
+    /// * `rule` - The applied rule.
+    ///
+    /// # Returns
+    /// * The builder with the phase added.
+    pub fn then<R: Rule + 'static>(self, rule: R) -> Self {
+        self.phase(&[RuleStep::exhaustive(rule)], false)
+    }
+
+    /// Adds a phase running the given steps until none of them applies a rule.
+    ///
+    /// # Arguments
+    /// * `steps` - A slice of the steps in the order they are run.
+    ///
+    /// # Returns
+    /// * The builder with the phase added.
+    pub fn repeat(self, steps: &[RuleStep]) -> Self {
+        self.phase(steps, true)
+    }
+
+    /// Adds a phase running the given steps.
+    ///
+    /// # Arguments
+    /// * `steps` - A slice of the steps in the order they are run.
+    /// * `repeat` - Whether the steps are run again as long as some of them applied a rule.
+    ///
+    /// # Returns
+    /// * The builder with the phase added.
+    pub fn phase(mut self, steps: &[RuleStep], repeat: bool) -> Self {
+        self.phases.push(MatchMergePhase {
+            steps: steps.to_vec(),
+            repeat,
+        });
+        self
+    }
+
+    /// Makes the algorithm report the components merged by the phases added so far.
+    ///
+    /// Without it the components are captured after the last phase.
+    ///
+    /// # Returns
+    /// * The builder capturing the components before the next phase.
+    pub fn capture_components(mut self) -> Self {
+        self.capture = Some(self.phases.len());
+        self
+    }
+
+    /// Makes the algorithm search the vertices in a random order instead of the order of their indices.
+    ///
+    /// # Arguments
+    /// * `seed` - The seed of the random number generator, the same seed giving the same order.
+    ///
+    /// # Returns
+    /// * The builder with the seed set.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Builds the algorithm.
+    ///
+    /// # Returns
+    /// * A `MatchMergeMps` running the phases.
+    pub fn build(self) -> MatchMergeMps {
+        MatchMergeMps {
+            name: self.name,
+            phases: self.phases,
+            capture: self.capture,
+            seed: self.seed,
+        }
+    }
+}
+
+/// A struct representing an MPS algorithm of the Match and Merge framework built from phases.
+#[derive(Clone)]
+pub struct MatchMergeMps {
+    name: &'static str,
+    phases: Vec<MatchMergePhase>,
+    capture: Option<usize>,
+    seed: Option<u64>,
+}
+
+impl MatchMergeMps {
+    /// Returns the phases of the algorithm.
+    ///
+    /// # Returns
+    /// * A slice of the phases in the order they are run.
+    pub fn phases(&self) -> &[MatchMergePhase] {
+        &self.phases
+    }
+
+    /// Returns the point at which the components are captured.
+    ///
+    /// # Returns
+    /// * `Some(usize)` with the index of the phase the components are captured before, `None` if they are captured
+    ///   after the last phase.
+    pub fn capture(&self) -> Option<usize> {
+        self.capture
+    }
 
-        None
+    /// Returns the seed of the random search order.
+    ///
+    /// # Returns
+    /// * `Some(u64)` with the seed if the search order is random, `None` if the vertices are searched in the order
+    ///   of their indices.
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
     }
 
-    /// Applies the K2 rule by adding an edge and connecting components.
+    /// Creates a copy of the algorithm searching the vertices in a random order.
     ///
     /// # Arguments
-    /// * `u` - Vertex index.
-    /// * `v` - Vertex index.
-    fn apply_k2_rule(&mut self, u: usize, v: usize) {
-        self.apply_rule(&[(u, v)], &[u, v]);
+    /// * `seed` - The seed of the random number generator, the same seed giving the same order.
+    ///
+    /// # Returns
+    /// * A new `MatchMergeMps` with the same phases and the given seed.
+    pub fn with_seed(&self, seed: u64) -> MatchMergeMps {
+        MatchMergeMps {
+            seed: Some(seed),
+            ..self.clone()
+        }
+    }
+}
+
+impl MpsAlgorithm for MatchMergeMps {
+    /// Computes the maximum planar subgraph by running the phases in order.
+    ///
+    /// # Arguments
+    /// * `g` - A reference to the input graph.
+    /// * `weights` - A reference to the weights of the edges.
+    /// * `constraints` - A reference to the mandatory and forbidden edges.
+    ///
+    /// # Returns
+    /// * `Ok(MpsResult)` containing the subgraph, the number of applications of each rule and the components
+    ///   at the capture point, or the `ConstraintError` if the mandatory edges cannot be kept.
+    fn solve_constrained(
+        &self,
+        g: &Graph,
+        weights: &EdgeWeights,
+        constraints: &EdgeConstraints,
+    ) -> Result<MpsResult, ConstraintError> {
+        let start = Instant::now();
+        let mut mm = MatchMerge::new(g, weights, constraints)?;
+        let mut components = None;
+
+        if let Some(seed) = self.seed {
+            mm.shuffle_order(seed);
+        }
+
+        for (i, phase) in self.phases.iter().enumerate() {
+            if self.capture == Some(i) {
+                components = Some(mm.component_labels());
+            }
+            mm.run_phase(phase);
+        }
+
+        let components = components.unwrap_or_else(|| mm.component_labels());
+        Ok(mm
+            .into_result(g, weights, start)
+            .with_components(components))
+    }
+
+    /// Returns the name of the algorithm.
+    ///
+    /// # Returns
+    /// * A string slice representing the name of the algorithm.
+    fn name(&self) -> &'static str {
+        self.name
     }
 }
 
@@ -309,26 +1230,44 @@ pub struct MyMps {}
 /// A struct representing the Poranen MPS algorithm.
 pub struct PoranenMps {}
 
+/// A struct representing an MPS algorithm merging the components along the longest fans found greedily.
+pub struct FanMps {
+    /// The maximum number of vertices of the paths of the fans, at least two.
+    pub max_length: usize,
+}
+
+impl CalinescuMps {
+    /// Builds the pipeline of the Calinescu algorithm, merging along triangles and then along edges.
+    ///
+    /// # Returns
+    /// * The algorithm running the phases, e.g. to be seeded or modified.
+    pub fn pipeline(&self) -> MatchMergeMps {
+        MatchMergeBuilder::new(self.name())
+            .then(MatchMergeRule::K3)
+            .capture_components()
+            .then(MatchMergeRule::K2)
+            .build()
+    }
+}
+
 impl MpsAlgorithm for CalinescuMps {
     /// Computes the maximum planar subgraph using the Calinescu algorithm.
     ///
     /// # Arguments
     /// * `g` - A reference to the input graph.
+    /// * `weights` - A reference to the weights of the edges.
+    /// * `constraints` - A reference to the mandatory and forbidden edges.
     ///
     /// # Returns
-    /// * The maximum planar subgraph of the input graph.
-    fn maximum_planar_subgraph(&self, g: &Graph) -> Graph {
-        let mut mm = MatchMerge::new(g);
-
-        while let Some((u, v, w)) = mm.find_k3_rule() {
-            mm.apply_k3_rule(u, v, w);
-        }
-
-        while let Some((u, v)) = mm.find_k2_rule() {
-            mm.apply_k2_rule(u, v);
-        }
-
-        mm.mps
+    /// * `Ok(MpsResult)` containing the subgraph, the number of applications of each rule and the components
+    ///   merged before the K2 phase, or the `ConstraintError` if the mandatory edges cannot be kept.
+    fn solve_constrained(
+        &self,
+        g: &Graph,
+        weights: &EdgeWeights,
+        constraints: &EdgeConstraints,
+    ) -> Result<MpsResult, ConstraintError> {
+        self.pipeline().solve_constrained(g, weights, constraints)
     }
 
     /// Returns the name of the algorithm.
@@ -340,30 +1279,39 @@ impl MpsAlgorithm for CalinescuMps {
     }
 }
 
+impl SchmidMps {
+    /// Builds the pipeline of the Schmid algorithm, merging along D4 structures, triangles and then edges.
+    ///
+    /// # Returns
+    /// * The algorithm running the phases, e.g. to be seeded or modified.
+    pub fn pipeline(&self) -> MatchMergeMps {
+        MatchMergeBuilder::new(self.name())
+            .then(MatchMergeRule::D4)
+            .then(MatchMergeRule::K3)
+            .capture_components()
+            .then(MatchMergeRule::K2)
+            .build()
+    }
+}
+
 impl MpsAlgorithm for SchmidMps {
     /// Computes the maximum planar subgraph using the Schmid algorithm.
     ///
     /// # Arguments
     /// * `g` - A reference to the input graph.
+    /// * `weights` - A reference to the weights of the edges.
+    /// * `constraints` - A reference to the mandatory and forbidden edges.
     ///
     /// # Returns
-    /// * The maximum planar subgraph of the input graph.
-    fn maximum_planar_subgraph(&self, g: &Graph) -> Graph {
-        let mut mm = MatchMerge::new(g);
-
-        while let Some((x, y, z, w)) = mm.find_d4_rule() {
-            mm.apply_d4_rule(x, y, z, w);
-        }
-
-        while let Some((u, v, w)) = mm.find_k3_rule() {
-            mm.apply_k3_rule(u, v, w);
-        }
-
-        while let Some((u, v)) = mm.find_k2_rule() {
-            mm.apply_k2_rule(u, v);
-        }
-
-        mm.mps
+    /// * `Ok(MpsResult)` containing the subgraph, the number of applications of each rule and the components
+    ///   merged before the K2 phase, or the `ConstraintError` if the mandatory edges cannot be kept.
+    fn solve_constrained(
+        &self,
+        g: &Graph,
+        weights: &EdgeWeights,
+        constraints: &EdgeConstraints,
+    ) -> Result<MpsResult, ConstraintError> {
+        self.pipeline().solve_constrained(g, weights, constraints)
     }
 
     /// Returns the name of the algorithm.
@@ -375,34 +1323,137 @@ impl MpsAlgorithm for SchmidMps {
     }
 }
 
+impl MyMps {
+    /// Builds the pipeline of the custom algorithm, merging along fans of length 4, D4 structures, triangles and then
+    /// edges.
+    ///
+    /// # Returns
+    /// * The algorithm running the phases, e.g. to be seeded or modified.
+    pub fn pipeline(&self) -> MatchMergeMps {
+        MatchMergeBuilder::new(self.name())
+            .then(MatchMergeRule::My)
+            .then(MatchMergeRule::D4)
+            .then(MatchMergeRule::K3)
+            .capture_components()
+            .then(MatchMergeRule::K2)
+            .build()
+    }
+}
+
 impl MpsAlgorithm for MyMps {
     /// Computes the maximum planar subgraph using a custom algorithm.
     ///
     /// # Arguments
     /// * `g` - A reference to the input graph.
+    /// * `weights` - A reference to the weights of the edges.
+    /// * `constraints` - A reference to the mandatory and forbidden edges.
     ///
     /// # Returns
-    /// * The maximum planar subgraph of the input graph.
-    fn maximum_planar_subgraph(&self, g: &Graph) -> Graph {
-        let mut mm = MatchMerge::new(g);
+    /// * `Ok(MpsResult)` containing the subgraph, the number of applications of each rule and the components
+    ///   merged before the K2 phase, or the `ConstraintError` if the mandatory edges cannot be kept.
+    fn solve_constrained(
+        &self,
+        g: &Graph,
+        weights: &EdgeWeights,
+        constraints: &EdgeConstraints,
+    ) -> Result<MpsResult, ConstraintError> {
+        self.pipeline().solve_constrained(g, weights, constraints)
+    }
 
-        while let Some((x, y, z, w, t)) = mm.find_my_rule() {
-            mm.apply_my_rule(x, y, z, w, t);
-        }
+    /// Returns the name of the algorithm.
+    ///
+    /// # Returns
+    /// * A string slice representing the name of the algorithm.
+    fn name(&self) -> &'static str {
+        "My"
+    }
+}
 
-        while let Some((x, y, z, w)) = mm.find_d4_rule() {
-            mm.apply_d4_rule(x, y, z, w);
-        }
+impl PoranenMps {
+    /// Builds the pipeline of the Poranen algorithm, alternating the Poranen rule with single triangles and then
+    /// merging along edges.
+    ///
+    /// # Returns
+    /// * The algorithm running the phases, e.g. to be seeded or modified.
+    pub fn pipeline(&self) -> MatchMergeMps {
+        MatchMergeBuilder::new(self.name())
+            .repeat(&[
+                RuleStep::exhaustive(MatchMergeRule::Poranen),
+                RuleStep::once(MatchMergeRule::K3),
+            ])
+            .capture_components()
+            .then(MatchMergeRule::K2)
+            .build()
+    }
+}
 
-        while let Some((u, v, w)) = mm.find_k3_rule() {
-            mm.apply_k3_rule(u, v, w);
-        }
+impl MpsAlgorithm for PoranenMps {
+    /// Computes the maximum planar subgraph using the Poranen algorithm.
+    ///
+    /// # Arguments
+    /// * `g` - A reference to the input graph.
+    /// * `weights` - A reference to the weights of the edges.
+    /// * `constraints` - A reference to the mandatory and forbidden edges.
+    ///
+    /// # Returns
+    /// * `Ok(MpsResult)` containing the subgraph, the number of applications of each rule and the components
+    ///   merged before the K2 phase, or the `ConstraintError` if the mandatory edges cannot be kept.
+    fn solve_constrained(
+        &self,
+        g: &Graph,
+        weights: &EdgeWeights,
+        constraints: &EdgeConstraints,
+    ) -> Result<MpsResult, ConstraintError> {
+        self.pipeline().solve_constrained(g, weights, constraints)
+    }
 
-        while let Some((u, v)) = mm.find_k2_rule() {
-            mm.apply_k2_rule(u, v);
-        }
+    /// Returns the name of the algorithm.
+    ///
+    /// # Returns
+    /// * A string slice representing the name of the algorithm.
+    fn name(&self) -> &'static str {
+        "Poranen"
+    }
+}
 
-        mm.mps
+impl FanMps {
+    /// Builds the pipeline of the algorithm, merging along the longest fans and then along edges.
+    ///
+    /// # Returns
+    /// * The algorithm running the phases, e.g. to be seeded or modified.
+    ///
+    /// # Panics
+    /// * If the maximum length is smaller than two.
+    pub fn pipeline(&self) -> MatchMergeMps {
+        MatchMergeBuilder::new(self.name())
+            .then(LongestFanRule::new(self.max_length))
+            .capture_components()
+            .then(MatchMergeRule::K2)
+            .build()
+    }
+}
+
+impl MpsAlgorithm for FanMps {
+    /// Computes the maximum planar subgraph by merging the components along the longest fans and then along edges.
+    ///
+    /// # Arguments
+    /// * `g` - A reference to the input graph.
+    /// * `weights` - A reference to the weights of the edges.
+    /// * `constraints` - A reference to the mandatory and forbidden edges.
+    ///
+    /// # Returns
+    /// * `Ok(MpsResult)` containing the subgraph, the number of applications of each rule and the components
+    ///   merged before the K2 phase, or the `ConstraintError` if the mandatory edges cannot be kept.
+    ///
+    /// # Panics
+    /// * If the maximum length is smaller than two.
+    fn solve_constrained(
+        &self,
+        g: &Graph,
+        weights: &EdgeWeights,
+        constraints: &EdgeConstraints,
+    ) -> Result<MpsResult, ConstraintError> {
+        self.pipeline().solve_constrained(g, weights, constraints)
     }
 
     /// Returns the name of the algorithm.
@@ -410,41 +1461,115 @@ impl MpsAlgorithm for MyMps {
     /// # Returns
     /// * A string slice representing the name of the algorithm.
     fn name(&self) -> &'static str {
-        "My"
+        "Fan"
     }
 }
 
-impl MpsAlgorithm for PoranenMps {
-    /// Computes the maximum planar subgraph using the Poranen algorithm.
+/// A struct representing a Match and Merge algorithm restarted with random search orders, keeping the best result.
+pub struct MultiStartMps {
+    /// The restarted algorithm, its own seed being ignored.
+    pub algorithm: MatchMergeMps,
+    /// The name reported for the restarted algorithm.
+    pub name: &'static str,
+    /// The number of restarts, at least one.
+    pub restarts: usize,
+    /// The seed of the first restart, the following ones using the next seeds.
+    pub seed: u64,
+    /// Whether the restarts run in parallel threads.
+    pub parallel: bool,
+}
+
+impl MultiStartMps {
+    /// Runs all restarts of the algorithm.
+    ///
+    /// The results do not depend on whether the restarts run in parallel, so they can be used to estimate the
+    /// variance of the quality of the algorithm.
     ///
     /// # Arguments
     /// * `g` - A reference to the input graph.
+    /// * `weights` - A reference to the weights of the edges.
+    /// * `constraints` - A reference to the mandatory and forbidden edges.
     ///
     /// # Returns
-    /// * The maximum planar subgraph of the input graph.
-    fn maximum_planar_subgraph(&self, g: &Graph) -> Graph {
-        let mut mm = MatchMerge::new(g);
-        let mut found = true;
-
-        while found {
-            found = false;
+    /// * `Ok(Vec<MpsResult>)` containing the results of the restarts in the order of their seeds, or the
+    ///   `ConstraintError` if the mandatory edges cannot be kept.
+    pub fn solve_all(
+        &self,
+        g: &Graph,
+        weights: &EdgeWeights,
+        constraints: &EdgeConstraints,
+    ) -> Result<Vec<MpsResult>, ConstraintError> {
+        let restart = |i: usize| {
+            self.algorithm
+                .with_seed(self.seed.wrapping_add(i as u64))
+                .solve_constrained(g, weights, constraints)
+        };
+
+        if !self.parallel {
+            return (0..self.restarts).map(restart).collect();
+        }
 
-            while let Some((x, y, z)) = mm.find_poranen_rule() {
-                mm.apply_poranen_rule(x, y, z);
-                found = true;
-            }
+        let workers = std::thread::available_parallelism()
+            .map_or(1, |n| n.get())
+            .clamp(1, self.restarts.max(1));
+        let mut results = std::thread::scope(|scope| {
+            let handles = (0..workers)
+                .map(|w| {
+                    scope.spawn(move || {
+                        (w..self.restarts)
+                            .step_by(workers)
+                            .map(|i| (i, restart(i)))
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().expect("restarts do not panic"))
+                .collect::<Vec<_>>()
+        });
+
+        results.sort_unstable_by_key(|&(i, _)| i);
+        results.into_iter().map(|(_, result)| result).collect()
+    }
+}
 
-            if let Some((u, v, w)) = mm.find_k3_rule() {
-                mm.apply_k3_rule(u, v, w);
-                found = true;
+impl MpsAlgorithm for MultiStartMps {
+    /// Computes the maximum planar subgraph by restarting the algorithm and keeping the heaviest subgraph.
+    ///
+    /// # Arguments
+    /// * `g` - A reference to the input graph.
+    /// * `weights` - A reference to the weights of the edges.
+    /// * `constraints` - A reference to the mandatory and forbidden edges.
+    ///
+    /// # Returns
+    /// * `Ok(MpsResult)` of the first restart with the heaviest subgraph, with the number of restarts under the
+    ///   `Restarts` counter, or the `ConstraintError` if the mandatory edges cannot be kept.
+    ///
+    /// # Panics
+    /// * If the number of restarts is zero.
+    fn solve_constrained(
+        &self,
+        g: &Graph,
+        weights: &EdgeWeights,
+        constraints: &EdgeConstraints,
+    ) -> Result<MpsResult, ConstraintError> {
+        assert!(self.restarts > 0, "the algorithm runs at least once");
+        let start = Instant::now();
+        let mut best: Option<MpsResult> = None;
+
+        for result in self.solve_all(g, weights, constraints)? {
+            if best.as_ref().is_none_or(|b| result.weight > b.weight) {
+                best = Some(result);
             }
         }
 
-        while let Some((u, v)) = mm.find_k2_rule() {
-            mm.apply_k2_rule(u, v);
-        }
+        let mut result = best.unwrap();
+        result.counters.insert("Restarts", self.restarts);
+        result.wall_time = start.elapsed();
 
-        mm.mps
+        Ok(result)
     }
 
     /// Returns the name of the algorithm.
@@ -452,6 +1577,6 @@ impl MpsAlgorithm for PoranenMps {
     /// # Returns
     /// * A string slice representing the name of the algorithm.
     fn name(&self) -> &'static str {
-        "Poranen"
+        self.name
     }
 }
@@ -1,7 +1,244 @@
+use crate::constraints::{ConstraintError, EdgeConstraints};
 use crate::graphs::Graph;
+use crate::ilp::IlpSolution;
+use crate::weights::EdgeWeights;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// An enum representing how good the subgraph returned by an MPS algorithm is known to be.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MpsStatus {
+    /// The subgraph carries no optimality guarantee, e.g. because it was found by a heuristic.
+    Heuristic,
+    /// The subgraph is proven to be a maximum planar subgraph.
+    Optimal,
+    /// An exact algorithm was stopped by its time limit before proving optimality.
+    TimeLimited,
+}
+
+/// A struct representing the result of running an MPS algorithm.
+#[derive(Clone, Debug)]
+pub struct MpsResult {
+    /// The planar subgraph found.
+    pub mps: Graph,
+    /// The edges of the input graph missing from the subgraph.
+    pub removed_edges: Vec<(usize, usize)>,
+    /// The wall-clock time of the run.
+    pub wall_time: Duration,
+    /// The optimality status of the subgraph.
+    pub status: MpsStatus,
+    /// The total weight of the subgraph, its number of edges for unit weights.
+    pub weight: f64,
+    /// The best known upper bound on the weight of a maximum planar subgraph.
+    pub upper_bound: f64,
+    /// Algorithm-specific counters, e.g. how many times each rule was applied.
+    pub counters: BTreeMap<&'static str, usize>,
+    /// The component of every vertex, for algorithms that build the subgraph by merging components.
+    pub components: Option<Vec<usize>>,
+}
+
+impl MpsResult {
+    /// Creates a result of a heuristic, bounded only by the trivial upper bound.
+    ///
+    /// # Arguments
+    /// * `g` - A reference to the input graph.
+    /// * `weights` - A reference to the weights of the edges.
+    /// * `mps` - The planar subgraph found.
+    /// * `wall_time` - The wall-clock time of the run.
+    ///
+    /// # Returns
+    /// * A new `MpsResult` with the `Heuristic` status.
+    pub fn heuristic(g: &Graph, weights: &EdgeWeights, mps: Graph, wall_time: Duration) -> Self {
+        let weight = weights.total_weight(&mps);
+        let upper_bound = weights.upper_bound(g).max(weight);
+        MpsResult::new(g, mps, wall_time, MpsStatus::Heuristic, weight, upper_bound)
+    }
+
+    /// Creates a result from the solution of an exact algorithm.
+    ///
+    /// # Arguments
+    /// * `g` - A reference to the input graph.
+    /// * `solution` - The solution found by the exact algorithm.
+    /// * `wall_time` - The wall-clock time of the run.
+    ///
+    /// # Returns
+    /// * A new `MpsResult` with the `Optimal` status if the solution is proven optimal, `TimeLimited` if the solver
+    ///   was stopped by the time limit and `Heuristic` otherwise.
+    pub fn exact(g: &Graph, solution: IlpSolution, wall_time: Duration) -> Self {
+        let status = if solution.is_optimal() {
+            MpsStatus::Optimal
+        } else if solution.time_limited {
+            MpsStatus::TimeLimited
+        } else {
+            MpsStatus::Heuristic
+        };
+        MpsResult::new(
+            g,
+            solution.mps,
+            wall_time,
+            status,
+            solution.weight,
+            solution.upper_bound,
+        )
+    }
+
+    /// Creates a result with no counters.
+    ///
+    /// # Arguments
+    /// * `g` - A reference to the input graph.
+    /// * `mps` - The planar subgraph found.
+    /// * `wall_time` - The wall-clock time of the run.
+    /// * `status` - The optimality status of the subgraph.
+    /// * `weight` - The total weight of the subgraph.
+    /// * `upper_bound` - The best known upper bound on the weight of a maximum planar subgraph.
+    ///
+    /// # Returns
+    /// * A new `MpsResult`.
+    fn new(
+        g: &Graph,
+        mps: Graph,
+        wall_time: Duration,
+        status: MpsStatus,
+        weight: f64,
+        upper_bound: f64,
+    ) -> Self {
+        MpsResult {
+            removed_edges: removed_edges(g, &mps),
+            mps,
+            wall_time,
+            status,
+            weight,
+            upper_bound,
+            counters: BTreeMap::new(),
+            components: None,
+        }
+    }
+
+    /// Replaces the subgraph of the result by a heavier one, e.g. found by a post-processing step.
+    ///
+    /// # Arguments
+    /// * `g` - A reference to the input graph.
+    /// * `weights` - A reference to the weights of the edges.
+    /// * `mps` - The new planar subgraph.
+    ///
+    /// # Returns
+    /// * The result with the new subgraph, its weight and removed edges.
+    pub fn with_mps(mut self, g: &Graph, weights: &EdgeWeights, mps: Graph) -> Self {
+        self.removed_edges = removed_edges(g, &mps);
+        self.weight = weights.total_weight(&mps);
+        self.upper_bound = self.upper_bound.max(self.weight);
+        self.mps = mps;
+        self
+    }
+
+    /// Replaces the counters of the result.
+    ///
+    /// # Arguments
+    /// * `counters` - A map from counter names to their values.
+    ///
+    /// # Returns
+    /// * The result with the given counters.
+    pub fn with_counters(mut self, counters: BTreeMap<&'static str, usize>) -> Self {
+        self.counters = counters;
+        self
+    }
+
+    /// Sets the components of the vertices.
+    ///
+    /// # Arguments
+    /// * `components` - A vector with the index of the component of every vertex.
+    ///
+    /// # Returns
+    /// * The result with the given components.
+    pub fn with_components(mut self, components: Vec<usize>) -> Self {
+        self.components = Some(components);
+        self
+    }
+
+    /// Computes the relative gap between the upper bound and the weight of the subgraph.
+    ///
+    /// # Returns
+    /// * The gap, between 0 and 1.
+    pub fn gap(&self) -> f64 {
+        relative_gap(self.weight, self.upper_bound)
+    }
+}
+
+/// Lists the edges of a graph missing from its subgraph.
+///
+/// # Arguments
+/// * `g` - A reference to the input graph.
+/// * `mps` - A reference to the subgraph.
+///
+/// # Returns
+/// * A vector of the edges of the graph that are not edges of the subgraph.
+fn removed_edges(g: &Graph, mps: &Graph) -> Vec<(usize, usize)> {
+    g.all_edges()
+        .into_iter()
+        .filter(|&(u, v)| !mps.has_edge(u, v))
+        .collect()
+}
+
+/// Computes the relative gap between a lower and an upper bound.
+///
+/// # Arguments
+/// * `lower_bound` - The weight of the best solution found.
+/// * `upper_bound` - The upper bound on the weight of an optimal solution.
+///
+/// # Returns
+/// * The gap, between 0 and 1.
+pub(crate) fn relative_gap(lower_bound: f64, upper_bound: f64) -> f64 {
+    if upper_bound <= 0.0 {
+        0.0
+    } else {
+        ((upper_bound - lower_bound) / upper_bound).clamp(0.0, 1.0)
+    }
+}
 
 /// A trait representing an algorithm for finding the Maximum Planar Subgraph (MPS).
 pub trait MpsAlgorithm {
+    /// Computes a maximum weight planar subgraph of the given graph containing all mandatory and none of the
+    /// forbidden edges and reports how it was obtained.
+    ///
+    /// # Arguments
+    /// * `g` - A reference to the input graph.
+    /// * `weights` - A reference to the weights of the edges.
+    /// * `constraints` - A reference to the mandatory and forbidden edges.
+    ///
+    /// # Returns
+    /// * `Ok(MpsResult)` containing the subgraph, its status, bounds, timing and counters, or the `ConstraintError`
+    ///   if no planar subgraph satisfies the constraints, e.g. because the mandatory edges are not planar.
+    fn solve_constrained(
+        &self,
+        g: &Graph,
+        weights: &EdgeWeights,
+        constraints: &EdgeConstraints,
+    ) -> Result<MpsResult, ConstraintError>;
+
+    /// Computes a maximum weight planar subgraph of the given graph and reports how it was obtained.
+    ///
+    /// # Arguments
+    /// * `g` - A reference to the input graph.
+    /// * `weights` - A reference to the weights of the edges.
+    ///
+    /// # Returns
+    /// * The result containing the subgraph, its status, bounds, timing and counters.
+    fn solve_weighted(&self, g: &Graph, weights: &EdgeWeights) -> MpsResult {
+        self.solve_constrained(g, weights, &EdgeConstraints::new())
+            .expect("a problem without fixed edges is always feasible")
+    }
+
+    /// Computes the maximum planar subgraph of the given graph and reports how it was obtained.
+    ///
+    /// # Arguments
+    /// * `g` - A reference to the input graph.
+    ///
+    /// # Returns
+    /// * The result containing the subgraph, its status, bounds, timing and counters.
+    fn solve(&self, g: &Graph) -> MpsResult {
+        self.solve_weighted(g, &EdgeWeights::new())
+    }
+
     /// Computes the maximum planar subgraph of the given graph.
     ///
     /// # Arguments
@@ -9,7 +246,9 @@ pub trait MpsAlgorithm {
     ///
     /// # Returns
     /// * The maximum planar subgraph of the input graph.
-    fn maximum_planar_subgraph(&self, g: &Graph) -> Graph;
+    fn maximum_planar_subgraph(&self, g: &Graph) -> Graph {
+        self.solve(g).mps
+    }
 
     /// Returns the name of the algorithm.
     ///
@@ -0,0 +1,387 @@
+use crate::graphs::{Graph, GraphError, MAX_PARSED_VERTICES};
+
+/// The optional header of graph6 files.
+const GRAPH6_HEADER: &str = ">>graph6<<";
+
+/// The optional header of sparse6 files.
+const SPARSE6_HEADER: &str = ">>sparse6<<";
+
+/// Builds a parse error.
+///
+/// # Arguments
+/// * `line` - The number of the line, starting from 1.
+/// * `message` - The description of the error.
+///
+/// # Returns
+/// * A `GraphError::Parse` with the given line and message.
+fn parse_error(line: usize, message: &str) -> GraphError {
+    GraphError::Parse {
+        line,
+        message: message.to_string(),
+    }
+}
+
+/// Encodes the number of vertices as in the nauty formats.
+///
+/// # Arguments
+/// * `n` - The number of vertices, at most `2^36 - 1`.
+///
+/// # Returns
+/// * The bytes encoding the number.
+fn encode_size(n: usize) -> Vec<u8> {
+    assert!(
+        n < 1 << 36,
+        "the nauty formats encode at most 2^36 - 1 vertices"
+    );
+    let chunks = |k: usize| (0..k).rev().map(move |i| ((n >> (6 * i)) & 63) as u8 + 63);
+
+    if n <= 62 {
+        vec![n as u8 + 63]
+    } else if n <= 258047 {
+        std::iter::once(126).chain(chunks(3)).collect()
+    } else {
+        [126, 126].into_iter().chain(chunks(6)).collect()
+    }
+}
+
+/// Decodes the number of vertices at the beginning of a nauty string.
+///
+/// # Arguments
+/// * `bytes` - The bytes of the string without the format prefix.
+/// * `line` - The number of the line, starting from 1, used in errors.
+///
+/// # Returns
+/// * `Ok((usize, &[u8]))` containing the number of vertices and the remaining bytes, or `Err(GraphError::Parse)`
+///   if the bytes do not start with a valid number.
+fn decode_size(bytes: &[u8], line: usize) -> Result<(usize, &[u8]), GraphError> {
+    let (k, start) = match bytes {
+        [126, 126, ..] => (6, 2),
+        [126, ..] => (3, 1),
+        [_, ..] => (1, 0),
+        [] => return Err(parse_error(line, "missing number of vertices")),
+    };
+
+    if bytes.len() < start + k {
+        return Err(parse_error(line, "truncated number of vertices"));
+    }
+
+    let mut n = 0;
+    for &byte in bytes[start..start + k].iter() {
+        if !(63..=126).contains(&byte) {
+            return Err(parse_error(line, "invalid character"));
+        }
+        n = (n << 6) | (byte - 63) as usize;
+    }
+
+    Ok((n, &bytes[start + k..]))
+}
+
+/// Packs bits into printable characters, six bits per character.
+///
+/// # Arguments
+/// * `bits` - A slice of bits, padded with zeros to a multiple of six.
+///
+/// # Returns
+/// * The bytes encoding the bits.
+fn pack_bits(bits: &[bool]) -> Vec<u8> {
+    bits.chunks(6)
+        .map(|chunk| {
+            let value = (0..6).fold(0, |acc, i| {
+                (acc << 1) | chunk.get(i).copied().unwrap_or(false) as u8
+            });
+            value + 63
+        })
+        .collect()
+}
+
+/// Unpacks printable characters into bits, six bits per character.
+///
+/// # Arguments
+/// * `bytes` - A slice of characters.
+/// * `line` - The number of the line, starting from 1, used in errors.
+///
+/// # Returns
+/// * `Ok(Vec<bool>)` containing the bits, or `Err(GraphError::Parse)` if a character is out of range.
+fn unpack_bits(bytes: &[u8], line: usize) -> Result<Vec<bool>, GraphError> {
+    let mut bits = Vec::with_capacity(6 * bytes.len());
+    for &byte in bytes {
+        if !(63..=126).contains(&byte) {
+            return Err(parse_error(line, "invalid character"));
+        }
+        bits.extend((0..6).rev().map(|i| (byte - 63) >> i & 1 == 1));
+    }
+    Ok(bits)
+}
+
+/// Computes the number of bits used by sparse6 for a vertex index.
+///
+/// # Arguments
+/// * `n` - The number of vertices.
+///
+/// # Returns
+/// * The number of bits needed to represent `n - 1`, at least one.
+fn sparse6_width(n: usize) -> usize {
+    let mut k = 1;
+    while (1 << k) < n {
+        k += 1;
+    }
+    k
+}
+
+/// Decodes a graph6 string.
+///
+/// # Arguments
+/// * `text` - The string, with or without the header.
+/// * `line` - The number of the line, starting from 1, used in errors.
+///
+/// # Returns
+/// * `Ok(Graph)` encoded by the string, or `Err(GraphError::Parse)` if it is malformed.
+fn decode_graph6(text: &str, line: usize) -> Result<Graph, GraphError> {
+    let text = text.strip_prefix(GRAPH6_HEADER).unwrap_or(text);
+    let (n, data) = decode_size(text.as_bytes(), line)?;
+
+    let num_of_bits = n
+        .checked_mul(n.saturating_sub(1))
+        .ok_or_else(|| parse_error(line, "too many vertices"))?
+        / 2;
+    if data.len() != num_of_bits.div_ceil(6) {
+        return Err(parse_error(line, "wrong length of the adjacency matrix"));
+    }
+
+    let bits = unpack_bits(data, line)?;
+    let mut graph = Graph::empty(n);
+    let mut k = 0;
+    for v in 1..n {
+        for u in 0..v {
+            if bits[k] {
+                graph.add_edge(u, v);
+            }
+            k += 1;
+        }
+    }
+
+    Ok(graph)
+}
+
+/// Decodes a sparse6 string.
+///
+/// # Arguments
+/// * `text` - The string, with or without the header.
+/// * `line` - The number of the line, starting from 1, used in errors.
+///
+/// # Returns
+/// * `Ok(Graph)` encoded by the string, or `Err(GraphError::Parse)` if it is malformed, contains loops or has more
+///   than `2^24` vertices.
+fn decode_sparse6(text: &str, line: usize) -> Result<Graph, GraphError> {
+    let text = text.strip_prefix(SPARSE6_HEADER).unwrap_or(text);
+    let data = text
+        .strip_prefix(':')
+        .ok_or_else(|| parse_error(line, "sparse6 strings start with `:`"))?;
+    let (n, data) = decode_size(data.as_bytes(), line)?;
+    if n > MAX_PARSED_VERTICES {
+        return Err(parse_error(line, "too many vertices"));
+    }
+
+    let bits = unpack_bits(data, line)?;
+    let k = sparse6_width(n);
+    let mut graph = Graph::empty(n);
+    let mut v = 0;
+    let mut position = 0;
+
+    while position + 1 + k <= bits.len() {
+        if bits[position] {
+            v += 1;
+        }
+        let x = bits[position + 1..position + 1 + k]
+            .iter()
+            .fold(0, |acc, &bit| (acc << 1) | bit as usize);
+        position += 1 + k;
+
+        // the padding may look like an overlarge vertex
+        if x >= n || v >= n {
+            break;
+        } else if x > v {
+            v = x;
+        } else if x == v {
+            return Err(parse_error(line, &format!("self loop at vertex {v}")));
+        } else {
+            graph.add_edge(x, v);
+        }
+    }
+
+    Ok(graph)
+}
+
+impl Graph {
+    /// Parses a graph in the graph6 format.
+    ///
+    /// # Arguments
+    /// * `text` - The graph6 string, optionally preceded by the `>>graph6<<` header.
+    ///
+    /// # Returns
+    /// * `Ok(Graph)` encoded by the string, or `Err(GraphError::Parse)` if it is malformed.
+    pub fn from_graph6(text: &str) -> Result<Graph, GraphError> {
+        decode_graph6(text.trim_end(), 1)
+    }
+
+    /// Encodes the graph in the graph6 format.
+    ///
+    /// # Returns
+    /// * The graph6 string without the header.
+    ///
+    /// # Panics
+    /// * If the graph has `2^36` or more vertices.
+    pub fn to_graph6(&self) -> String {
+        let n = self.num_of_vertices();
+
+        let mut bits = Vec::with_capacity(n * n.saturating_sub(1) / 2);
+        for v in 1..n {
+            for u in 0..v {
+                bits.push(self.has_edge(u, v));
+            }
+        }
+
+        let mut bytes = encode_size(n);
+        bytes.extend(pack_bits(&bits));
+        bytes.into_iter().map(char::from).collect()
+    }
+
+    /// Parses a graph in the sparse6 format.
+    ///
+    /// Multiple edges are read once. The incremental variant starting with `;` is not supported.
+    ///
+    /// # Arguments
+    /// * `text` - The sparse6 string starting with `:`, optionally preceded by the `>>sparse6<<` header.
+    ///
+    /// # Returns
+    /// * `Ok(Graph)` encoded by the string, or `Err(GraphError::Parse)` if it is malformed, contains loops or has
+    ///   more than `2^24` vertices.
+    pub fn from_sparse6(text: &str) -> Result<Graph, GraphError> {
+        decode_sparse6(text.trim_end(), 1)
+    }
+
+    /// Encodes the graph in the sparse6 format.
+    ///
+    /// # Returns
+    /// * The sparse6 string starting with `:` without the header.
+    ///
+    /// # Panics
+    /// * If the graph has `2^36` or more vertices.
+    pub fn to_sparse6(&self) -> String {
+        let n = self.num_of_vertices();
+
+        let k = sparse6_width(n);
+        let encode = |bits: &mut Vec<bool>, x: usize| {
+            bits.extend((0..k).rev().map(|i| (x >> i) & 1 == 1));
+        };
+
+        // the edges sorted by the larger end, then by the smaller one
+        let mut edges = self
+            .all_edges()
+            .into_iter()
+            .map(|(u, v)| (v, u))
+            .collect::<Vec<_>>();
+        edges.sort_unstable();
+
+        let mut bits = Vec::new();
+        let mut current = 0;
+        for (v, u) in edges {
+            if v == current {
+                bits.push(false);
+                encode(&mut bits, u);
+            } else if v == current + 1 {
+                current = v;
+                bits.push(true);
+                encode(&mut bits, u);
+            } else {
+                current = v;
+                bits.push(true);
+                encode(&mut bits, v);
+                bits.push(false);
+                encode(&mut bits, u);
+            }
+        }
+
+        // the padding must not be read as a loop at the last vertex
+        let padding = (6 - bits.len() % 6) % 6;
+        if k < 6 && n == (1 << k) && padding > k && current == n - 2 {
+            bits.push(false);
+        }
+        let padding = (6 - bits.len() % 6) % 6;
+        bits.extend(std::iter::repeat_n(true, padding));
+
+        let mut bytes = vec![b':'];
+        bytes.extend(encode_size(n));
+        bytes.extend(pack_bits(&bits));
+        bytes.into_iter().map(char::from).collect()
+    }
+
+    /// Parses graphs in the graph6 or sparse6 format, one graph per line.
+    ///
+    /// The format of every line is recognized by its first character, so both formats may be mixed. Empty lines
+    /// are skipped.
+    ///
+    /// # Arguments
+    /// * `text` - The lines to parse, e.g. the output of `geng`.
+    ///
+    /// # Returns
+    /// * `Ok(Vec<Graph>)` containing the graphs in order, or `Err(GraphError::Parse)` pointing to the first
+    ///   malformed line.
+    pub fn from_nauty_lines(text: &str) -> Result<Vec<Graph>, GraphError> {
+        let mut graphs = Vec::new();
+
+        for (i, content) in text.lines().enumerate() {
+            let content = content.trim();
+            if content.is_empty() {
+                continue;
+            }
+
+            let graph = if content.starts_with(':') || content.starts_with(SPARSE6_HEADER) {
+                decode_sparse6(content, i + 1)?
+            } else if content.starts_with(';') {
+                return Err(parse_error(i + 1, "incremental sparse6 is not supported"));
+            } else {
+                decode_graph6(content, i + 1)?
+            };
+            graphs.push(graph);
+        }
+
+        Ok(graphs)
+    }
+
+    /// Reads graphs in the graph6 or sparse6 format from a file with one graph per line.
+    ///
+    /// # Arguments
+    /// * `filename` - The name of the file to read the graphs from.
+    ///
+    /// # Returns
+    /// * `Ok(Vec<Graph>)` containing the graphs in order, or the `GraphError` describing why they could not be read.
+    pub fn read_nauty(filename: &str) -> Result<Vec<Graph>, GraphError> {
+        Graph::from_nauty_lines(&std::fs::read_to_string(filename)?)
+    }
+
+    /// Writes graphs to a file with one graph per line.
+    ///
+    /// # Arguments
+    /// * `graphs` - A slice of the graphs to write.
+    /// * `filename` - The name of the file to write the graphs to.
+    /// * `sparse` - Whether to use sparse6 instead of graph6.
+    ///
+    /// # Returns
+    /// * `Ok(())` if the file was written, or the `GraphError` describing why it could not be.
+    pub fn write_nauty(graphs: &[Graph], filename: &str, sparse: bool) -> Result<(), GraphError> {
+        let mut text = String::new();
+
+        for graph in graphs {
+            let line = if sparse {
+                graph.to_sparse6()
+            } else {
+                graph.to_graph6()
+            };
+            text.push_str(&line);
+            text.push('\n');
+        }
+
+        Ok(std::fs::write(filename, text)?)
+    }
+}
@@ -0,0 +1,1148 @@
+use crate::graphs::Graph;
+use std::collections::{HashMap, HashSet};
+
+/// A directed edge (arc) represented as a tuple of vertex indices.
+type Arc = (usize, usize);
+
+/// A struct representing a combinatorial planar embedding of a graph.
+///
+/// The embedding is stored as a rotation system, i.e. the clockwise cyclic order of neighbors around each vertex,
+/// together with the faces induced by this rotation system.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlanarEmbedding {
+    rotation: Vec<Vec<usize>>,
+    faces: Vec<Vec<usize>>,
+}
+
+impl PlanarEmbedding {
+    /// Creates an embedding from a rotation system and computes its faces.
+    ///
+    /// # Arguments
+    /// * `rotation` - A vector containing the clockwise cyclic order of neighbors for each vertex.
+    ///
+    /// # Returns
+    /// * A new instance of `PlanarEmbedding`.
+    pub fn from_rotation_system(rotation: Vec<Vec<usize>>) -> Self {
+        let faces = trace_faces(&rotation);
+        PlanarEmbedding { rotation, faces }
+    }
+
+    /// Returns the clockwise cyclic order of neighbors around a given vertex.
+    ///
+    /// # Arguments
+    /// * `vertex` - The index of the vertex.
+    ///
+    /// # Returns
+    /// * An `Option` containing a reference to the ordered neighbors if the vertex is valid, `None` otherwise.
+    pub fn rotation(&self, vertex: usize) -> Option<&Vec<usize>> {
+        self.rotation.get(vertex)
+    }
+
+    /// Returns the rotation system of the embedding.
+    ///
+    /// # Returns
+    /// * A reference to the vector of clockwise neighbor orders, indexed by vertex.
+    pub fn rotation_system(&self) -> &Vec<Vec<usize>> {
+        &self.rotation
+    }
+
+    /// Returns the faces of the embedding.
+    ///
+    /// Each face is given as the closed walk of vertices along its boundary. The outer face of every connected
+    /// component with at least one edge is reported separately; isolated vertices do not bound any face.
+    ///
+    /// # Returns
+    /// * A reference to the vector of faces.
+    pub fn faces(&self) -> &Vec<Vec<usize>> {
+        &self.faces
+    }
+
+    /// Returns the number of faces in the embedding.
+    ///
+    /// # Returns
+    /// * The number of faces.
+    pub fn num_of_faces(&self) -> usize {
+        self.faces.len()
+    }
+}
+
+/// Traces the faces of a rotation system.
+///
+/// # Arguments
+/// * `rotation` - A slice containing the clockwise cyclic order of neighbors for each vertex.
+///
+/// # Returns
+/// * A vector of faces, each given as the closed walk of vertices along its boundary.
+fn trace_faces(rotation: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    // position of every neighbor in the rotation of a vertex
+    let position = rotation
+        .iter()
+        .map(|order| {
+            order
+                .iter()
+                .enumerate()
+                .map(|(i, &u)| (u, i))
+                .collect::<HashMap<usize, usize>>()
+        })
+        .collect::<Vec<_>>();
+
+    let mut visited = rotation
+        .iter()
+        .map(|order| vec![false; order.len()])
+        .collect::<Vec<_>>();
+    let mut faces = Vec::new();
+
+    for v in 0..rotation.len() {
+        for i in 0..rotation[v].len() {
+            if visited[v][i] {
+                continue;
+            }
+
+            // the face lies to the right of the arc: after entering w from u, leave along the
+            // neighbor preceding u in the clockwise order around w
+            let mut face = Vec::new();
+            let (mut from, mut index) = (v, i);
+            while !visited[from][index] {
+                visited[from][index] = true;
+                face.push(from);
+                let to = rotation[from][index];
+                let degree = rotation[to].len();
+                index = (position[to][&from] + degree - 1) % degree;
+                from = to;
+            }
+
+            faces.push(face);
+        }
+    }
+
+    faces
+}
+
+/// An interval of return edges on the conflict stack of the left-right planarity test.
+#[derive(Clone, Copy, Default)]
+struct Interval {
+    low: Option<Arc>,
+    high: Option<Arc>,
+}
+
+impl Interval {
+    /// Creates an interval bounded by the given arcs.
+    ///
+    /// # Arguments
+    /// * `low` - The lowest return edge of the interval.
+    /// * `high` - The highest return edge of the interval.
+    ///
+    /// # Returns
+    /// * A new instance of `Interval`.
+    fn new(low: Arc, high: Arc) -> Self {
+        Interval {
+            low: Some(low),
+            high: Some(high),
+        }
+    }
+
+    /// Checks if the interval is empty.
+    ///
+    /// # Returns
+    /// * `true` if the interval contains no return edges, `false` otherwise.
+    fn is_empty(&self) -> bool {
+        self.low.is_none() && self.high.is_none()
+    }
+
+    /// Checks if the interval conflicts with the given arc.
+    ///
+    /// # Arguments
+    /// * `b` - The arc to compare with.
+    /// * `lowpt` - A hashmap mapping arcs to their lowpoints.
+    ///
+    /// # Returns
+    /// * `true` if the highest return edge of the interval returns above `b`, `false` otherwise.
+    fn conflicting(&self, b: Arc, lowpt: &HashMap<Arc, usize>) -> bool {
+        match self.high {
+            Some(high) => lowpt[&high] > lowpt[&b],
+            None => false,
+        }
+    }
+}
+
+/// A pair of intervals that have to be embedded on different sides.
+#[derive(Clone, Copy, Default)]
+struct ConflictPair {
+    left: Interval,
+    right: Interval,
+}
+
+impl ConflictPair {
+    /// Swaps the left and the right interval.
+    fn swap(&mut self) {
+        std::mem::swap(&mut self.left, &mut self.right);
+    }
+
+    /// Returns the lowest lowpoint of the pair.
+    ///
+    /// # Arguments
+    /// * `lowpt` - A hashmap mapping arcs to their lowpoints.
+    ///
+    /// # Returns
+    /// * The lowest lowpoint among the return edges of both intervals.
+    fn lowest(&self, lowpt: &HashMap<Arc, usize>) -> usize {
+        match (self.left.low, self.right.low) {
+            (None, Some(r)) => lowpt[&r],
+            (Some(l), None) => lowpt[&l],
+            (Some(l), Some(r)) => lowpt[&l].min(lowpt[&r]),
+            (None, None) => usize::MAX,
+        }
+    }
+}
+
+/// A half-edge based embedding under construction.
+///
+/// For every vertex it keeps its first neighbor and, for every neighbor, the clockwise and counterclockwise
+/// successors in the cyclic order.
+struct HalfEdges {
+    first: Vec<Option<usize>>,
+    links: Vec<HashMap<usize, (usize, usize)>>,
+}
+
+impl HalfEdges {
+    /// Creates an empty half-edge structure.
+    ///
+    /// # Arguments
+    /// * `n` - The number of vertices.
+    ///
+    /// # Returns
+    /// * A new instance of `HalfEdges`.
+    fn new(n: usize) -> Self {
+        HalfEdges {
+            first: vec![None; n],
+            links: vec![HashMap::new(); n],
+        }
+    }
+
+    /// Adds the half-edge `start -> end` clockwise after the reference neighbor.
+    ///
+    /// # Arguments
+    /// * `start` - The vertex around which the half-edge is inserted.
+    /// * `end` - The other endpoint of the half-edge.
+    /// * `reference` - The neighbor after which `end` is inserted, or `None` if `start` has no neighbors yet.
+    fn add_cw(&mut self, start: usize, end: usize, reference: Option<usize>) {
+        let Some(reference) = reference else {
+            self.links[start].insert(end, (end, end));
+            self.first[start] = Some(end);
+            return;
+        };
+
+        let links = &mut self.links[start];
+        let cw_reference = links[&reference].0;
+        links.get_mut(&reference).unwrap().0 = end;
+        links.insert(end, (cw_reference, reference));
+        links.get_mut(&cw_reference).unwrap().1 = end;
+    }
+
+    /// Adds the half-edge `start -> end` counterclockwise before the reference neighbor.
+    ///
+    /// # Arguments
+    /// * `start` - The vertex around which the half-edge is inserted.
+    /// * `end` - The other endpoint of the half-edge.
+    /// * `reference` - The neighbor before which `end` is inserted, or `None` if `start` has no neighbors yet.
+    fn add_ccw(&mut self, start: usize, end: usize, reference: Option<usize>) {
+        let Some(reference) = reference else {
+            self.add_cw(start, end, None);
+            return;
+        };
+
+        let ccw_reference = self.links[start][&reference].1;
+        self.add_cw(start, end, Some(ccw_reference));
+        if self.first[start] == Some(reference) {
+            self.first[start] = Some(end);
+        }
+    }
+
+    /// Adds the half-edge `start -> end` as the first neighbor of `start`.
+    ///
+    /// # Arguments
+    /// * `start` - The vertex around which the half-edge is inserted.
+    /// * `end` - The other endpoint of the half-edge.
+    fn add_first(&mut self, start: usize, end: usize) {
+        let reference = self.first[start];
+        self.add_ccw(start, end, reference);
+    }
+
+    /// Converts the half-edge structure into a rotation system.
+    ///
+    /// # Returns
+    /// * A vector containing the clockwise cyclic order of neighbors for each vertex.
+    fn into_rotation_system(self) -> Vec<Vec<usize>> {
+        self.first
+            .iter()
+            .zip(self.links.iter())
+            .map(|(first, links)| {
+                let mut order = Vec::with_capacity(links.len());
+                if let Some(first) = *first {
+                    let mut current = first;
+                    loop {
+                        order.push(current);
+                        current = links[&current].0;
+                        if current == first {
+                            break;
+                        }
+                    }
+                }
+                order
+            })
+            .collect()
+    }
+}
+
+/// The state of the left-right planarity test.
+///
+/// The implementation follows Ulrik Brandes, "The Left-Right Planarity Test" (2009), with all depth-first
+/// searches written iteratively so that deep graphs do not overflow the call stack.
+struct LrState {
+    adjs: Vec<Vec<usize>>,
+    roots: Vec<usize>,
+    height: Vec<Option<usize>>,
+    parent_edge: Vec<Option<Arc>>,
+    out_arcs: Vec<Vec<usize>>,
+    lowpt: HashMap<Arc, usize>,
+    lowpt2: HashMap<Arc, usize>,
+    nesting_depth: HashMap<Arc, isize>,
+    ordered_adjs: Vec<Vec<usize>>,
+    conflicts: Vec<ConflictPair>,
+    stack_bottom: HashMap<Arc, usize>,
+    lowpt_edge: HashMap<Arc, Arc>,
+    reference: HashMap<Arc, Arc>,
+    side: HashMap<Arc, isize>,
+}
+
+impl LrState {
+    /// Creates the initial state for the given graph.
+    ///
+    /// # Arguments
+    /// * `g` - A reference to the input graph.
+    ///
+    /// # Returns
+    /// * A new instance of `LrState`.
+    fn new(g: &Graph) -> Self {
+        let n = g.num_of_vertices();
+        let adjs = g
+            .vertices()
+            .map(|v| {
+                let mut neighbors = g.neighbors(v).unwrap().iter().cloned().collect::<Vec<_>>();
+                neighbors.sort_unstable();
+                neighbors
+            })
+            .collect::<Vec<_>>();
+
+        LrState {
+            adjs,
+            roots: Vec::new(),
+            height: vec![None; n],
+            parent_edge: vec![None; n],
+            out_arcs: vec![Vec::new(); n],
+            lowpt: HashMap::new(),
+            lowpt2: HashMap::new(),
+            nesting_depth: HashMap::new(),
+            ordered_adjs: Vec::new(),
+            conflicts: Vec::new(),
+            stack_bottom: HashMap::new(),
+            lowpt_edge: HashMap::new(),
+            reference: HashMap::new(),
+            side: HashMap::new(),
+        }
+    }
+
+    /// Orients the graph along a depth-first search and computes lowpoints and nesting depths.
+    ///
+    /// # Arguments
+    /// * `root` - The root of the depth-first search tree.
+    fn dfs_orientation(&mut self, root: usize) {
+        let mut stack = vec![root];
+        let mut index = vec![0; self.adjs.len()];
+        let mut oriented = HashSet::new();
+
+        while let Some(&v) = stack.last() {
+            let e = self.parent_edge[v];
+            let height_v = self.height[v].unwrap();
+            let mut descended = false;
+
+            while index[v] < self.adjs[v].len() {
+                let w = self.adjs[v][index[v]];
+                let vw = (v, w);
+
+                if !oriented.contains(&vw) {
+                    if oriented.contains(&(w, v)) {
+                        index[v] += 1;
+                        continue;
+                    }
+
+                    oriented.insert(vw);
+                    self.out_arcs[v].push(w);
+                    self.lowpt.insert(vw, height_v);
+                    self.lowpt2.insert(vw, height_v);
+
+                    match self.height[w] {
+                        None => {
+                            // tree edge, return here after w is finished
+                            self.parent_edge[w] = Some(vw);
+                            self.height[w] = Some(height_v + 1);
+                            stack.push(w);
+                            descended = true;
+                            break;
+                        }
+                        Some(height_w) => {
+                            // back edge
+                            self.lowpt.insert(vw, height_w);
+                        }
+                    }
+                }
+
+                // determine nesting depth
+                let lowpt_vw = self.lowpt[&vw];
+                let lowpt2_vw = self.lowpt2[&vw];
+                let mut depth = 2 * lowpt_vw as isize;
+                if lowpt2_vw < height_v {
+                    // chordal
+                    depth += 1;
+                }
+                self.nesting_depth.insert(vw, depth);
+
+                // update lowpoints of the parent edge
+                if let Some(e) = e {
+                    let lowpt_e = self.lowpt[&e];
+                    let lowpt2_e = self.lowpt2[&e];
+                    if lowpt_vw < lowpt_e {
+                        self.lowpt2.insert(e, lowpt_e.min(lowpt2_vw));
+                        self.lowpt.insert(e, lowpt_vw);
+                    } else if lowpt_vw > lowpt_e {
+                        self.lowpt2.insert(e, lowpt2_e.min(lowpt_vw));
+                    } else {
+                        self.lowpt2.insert(e, lowpt2_e.min(lowpt2_vw));
+                    }
+                }
+
+                index[v] += 1;
+            }
+
+            if !descended {
+                stack.pop();
+            }
+        }
+    }
+
+    /// Tests the left-right constraints along the oriented depth-first search tree.
+    ///
+    /// # Arguments
+    /// * `root` - The root of the depth-first search tree.
+    ///
+    /// # Returns
+    /// * `true` if the constraints can be satisfied, `false` if the component is not planar.
+    fn dfs_testing(&mut self, root: usize) -> bool {
+        let mut stack = vec![root];
+        let mut index = vec![0; self.adjs.len()];
+        let mut returned = HashSet::new();
+
+        while let Some(v) = stack.pop() {
+            let e = self.parent_edge[v];
+            let height_v = self.height[v].unwrap();
+            let mut descended = false;
+
+            while index[v] < self.ordered_adjs[v].len() {
+                let w = self.ordered_adjs[v][index[v]];
+                let ei = (v, w);
+
+                if !returned.contains(&ei) {
+                    self.stack_bottom.insert(ei, self.conflicts.len());
+
+                    if self.parent_edge[w] == Some(ei) {
+                        // tree edge, return here after w is finished
+                        returned.insert(ei);
+                        stack.push(v);
+                        stack.push(w);
+                        descended = true;
+                        break;
+                    }
+
+                    // back edge
+                    self.lowpt_edge.insert(ei, ei);
+                    self.conflicts.push(ConflictPair {
+                        left: Interval::default(),
+                        right: Interval::new(ei, ei),
+                    });
+                }
+
+                // integrate new return edges
+                if self.lowpt[&ei] < height_v {
+                    let e = e.unwrap();
+                    if index[v] == 0 {
+                        let lowpt_edge = self.lowpt_edge[&ei];
+                        self.lowpt_edge.insert(e, lowpt_edge);
+                    } else if !self.add_constraints(ei, e) {
+                        return false;
+                    }
+                }
+
+                index[v] += 1;
+            }
+
+            if !descended {
+                if let Some(e) = e {
+                    self.remove_back_edges(e);
+                }
+            }
+        }
+
+        true
+    }
+
+    /// Adds the constraints of the arc `ei` to the conflict stack.
+    ///
+    /// # Arguments
+    /// * `ei` - The outgoing arc whose return edges are integrated.
+    /// * `e` - The parent edge of the tail of `ei`.
+    ///
+    /// # Returns
+    /// * `true` if the constraints are consistent, `false` if the graph is not planar.
+    fn add_constraints(&mut self, ei: Arc, e: Arc) -> bool {
+        let mut p = ConflictPair::default();
+
+        // merge return edges of ei into p.right
+        loop {
+            let mut q = self.conflicts.pop().unwrap();
+            if !q.left.is_empty() {
+                q.swap();
+            }
+            if !q.left.is_empty() {
+                return false;
+            }
+
+            let q_right_low = q.right.low.unwrap();
+            if self.lowpt[&q_right_low] > self.lowpt[&e] {
+                // merge intervals
+                if p.right.is_empty() {
+                    p.right = q.right;
+                } else {
+                    self.set_reference(p.right.low, q.right.high);
+                }
+                p.right.low = q.right.low;
+            } else {
+                // align
+                self.set_reference(q.right.low, self.lowpt_edge.get(&e).cloned());
+            }
+
+            if self.conflicts.len() == self.stack_bottom[&ei] {
+                break;
+            }
+        }
+
+        // merge conflicting return edges of the previous siblings into p.left
+        while let Some(&top) = self.conflicts.last() {
+            if !top.left.conflicting(ei, &self.lowpt) && !top.right.conflicting(ei, &self.lowpt) {
+                break;
+            }
+
+            let mut q = self.conflicts.pop().unwrap();
+            if q.right.conflicting(ei, &self.lowpt) {
+                q.swap();
+            }
+            if q.right.conflicting(ei, &self.lowpt) {
+                return false;
+            }
+
+            // merge interval below lowpt(ei) into p.right
+            self.set_reference(p.right.low, q.right.high);
+            if q.right.low.is_some() {
+                p.right.low = q.right.low;
+            }
+
+            if p.left.is_empty() {
+                p.left = q.left;
+            } else {
+                self.set_reference(p.left.low, q.left.high);
+            }
+            p.left.low = q.left.low;
+        }
+
+        if !p.left.is_empty() || !p.right.is_empty() {
+            self.conflicts.push(p);
+        }
+
+        true
+    }
+
+    /// Removes the back edges returning to the tail of the parent edge.
+    ///
+    /// # Arguments
+    /// * `e` - The parent edge whose subtree is finished.
+    fn remove_back_edges(&mut self, e: Arc) {
+        let u = e.0;
+        let height_u = self.height[u].unwrap();
+
+        // trim back edges ending at the parent u, dropping entire conflict pairs
+        while let Some(top) = self.conflicts.last() {
+            if top.lowest(&self.lowpt) != height_u {
+                break;
+            }
+            let p = self.conflicts.pop().unwrap();
+            if let Some(low) = p.left.low {
+                self.side.insert(low, -1);
+            }
+        }
+
+        // one more conflict pair to consider
+        if let Some(mut p) = self.conflicts.pop() {
+            // trim the left interval
+            while let Some(high) = p.left.high {
+                if high.1 != u {
+                    break;
+                }
+                p.left.high = self.reference.get(&high).cloned();
+            }
+            if p.left.high.is_none() {
+                if let Some(low) = p.left.low {
+                    // just emptied
+                    self.set_reference(Some(low), p.right.low);
+                    self.side.insert(low, -1);
+                    p.left.low = None;
+                }
+            }
+
+            // trim the right interval
+            while let Some(high) = p.right.high {
+                if high.1 != u {
+                    break;
+                }
+                p.right.high = self.reference.get(&high).cloned();
+            }
+            if p.right.high.is_none() {
+                if let Some(low) = p.right.low {
+                    // just emptied
+                    self.set_reference(Some(low), p.left.low);
+                    self.side.insert(low, -1);
+                    p.right.low = None;
+                }
+            }
+
+            self.conflicts.push(p);
+        }
+
+        // side of e is the side of a highest return edge
+        if self.lowpt[&e] < height_u {
+            if let Some(top) = self.conflicts.last() {
+                let hl = top.left.high;
+                let hr = top.right.high;
+                let high = match (hl, hr) {
+                    (Some(l), Some(r)) if self.lowpt[&l] > self.lowpt[&r] => hl,
+                    (Some(_), None) => hl,
+                    _ => hr,
+                };
+                self.set_reference(Some(e), high);
+            }
+        }
+    }
+
+    /// Sets or clears the reference of an arc.
+    ///
+    /// # Arguments
+    /// * `key` - The arc whose reference is changed; nothing happens if it is `None`.
+    /// * `value` - The new reference, or `None` to clear it.
+    fn set_reference(&mut self, key: Option<Arc>, value: Option<Arc>) {
+        let Some(key) = key else {
+            return;
+        };
+
+        match value {
+            Some(value) => {
+                self.reference.insert(key, value);
+            }
+            None => {
+                self.reference.remove(&key);
+            }
+        }
+    }
+
+    /// Resolves the final side of an arc by following its chain of references.
+    ///
+    /// # Arguments
+    /// * `e` - The arc whose side is resolved.
+    ///
+    /// # Returns
+    /// * `1` if the arc is embedded on the right, `-1` if on the left.
+    fn sign(&mut self, e: Arc) -> isize {
+        let mut chain = vec![e];
+        while let Some(&next) = self.reference.get(chain.last().unwrap()) {
+            chain.push(next);
+        }
+
+        let mut sign = *self.side.get(chain.last().unwrap()).unwrap_or(&1);
+        for arc in chain.iter().rev().skip(1) {
+            sign *= *self.side.get(arc).unwrap_or(&1);
+            self.side.insert(*arc, sign);
+            self.reference.remove(arc);
+        }
+
+        sign
+    }
+
+    /// Inserts the back edges into the embedding along the depth-first search tree.
+    ///
+    /// # Arguments
+    /// * `root` - The root of the depth-first search tree.
+    /// * `embedding` - The half-edge structure being built.
+    fn dfs_embedding(&self, root: usize, embedding: &mut HalfEdges) {
+        let n = self.adjs.len();
+        let mut stack = vec![root];
+        let mut index = vec![0; n];
+        let mut left_ref = vec![None; n];
+        let mut right_ref = vec![None; n];
+
+        while let Some(v) = stack.pop() {
+            while index[v] < self.ordered_adjs[v].len() {
+                let w = self.ordered_adjs[v][index[v]];
+                let ei = (v, w);
+                index[v] += 1;
+
+                if self.parent_edge[w] == Some(ei) {
+                    // tree edge
+                    embedding.add_first(w, v);
+                    left_ref[v] = Some(w);
+                    right_ref[v] = Some(w);
+                    stack.push(v);
+                    stack.push(w);
+                    break;
+                }
+
+                // back edge
+                if *self.side.get(&ei).unwrap_or(&1) == 1 {
+                    embedding.add_cw(w, v, right_ref[w]);
+                } else {
+                    embedding.add_ccw(w, v, left_ref[w]);
+                    left_ref[w] = Some(v);
+                }
+            }
+        }
+    }
+}
+
+/// Computes a planar embedding of a graph using the left-right planarity test.
+///
+/// # Arguments
+/// * `g` - A reference to the input graph.
+///
+/// # Returns
+/// * `Some(PlanarEmbedding)` if the graph is planar, `None` otherwise.
+pub fn lr_planar_embedding(g: &Graph) -> Option<PlanarEmbedding> {
+    let n = g.num_of_vertices();
+    let m = g.num_of_edges();
+
+    if n > 2 && m > 3 * n - 6 {
+        return None;
+    }
+
+    let mut state = LrState::new(g);
+
+    // orientation phase
+    for v in 0..n {
+        if state.height[v].is_none() {
+            state.height[v] = Some(0);
+            state.roots.push(v);
+            state.dfs_orientation(v);
+        }
+    }
+
+    // testing phase
+    state.ordered_adjs = state
+        .out_arcs
+        .iter()
+        .enumerate()
+        .map(|(v, out)| {
+            let mut ordered = out.clone();
+            ordered.sort_by_key(|&w| state.nesting_depth[&(v, w)]);
+            ordered
+        })
+        .collect();
+
+    for root in state.roots.clone() {
+        if !state.dfs_testing(root) {
+            return None;
+        }
+    }
+
+    // embedding phase
+    let arcs = state
+        .out_arcs
+        .iter()
+        .enumerate()
+        .flat_map(|(v, out)| out.iter().map(move |&w| (v, w)))
+        .collect::<Vec<Arc>>();
+    for e in arcs {
+        let sign = state.sign(e);
+        *state.nesting_depth.get_mut(&e).unwrap() *= sign;
+    }
+
+    let mut embedding = HalfEdges::new(n);
+    for v in 0..n {
+        let mut ordered = state.out_arcs[v].clone();
+        ordered.sort_by_key(|&w| state.nesting_depth[&(v, w)]);
+
+        let mut previous = None;
+        for &w in ordered.iter() {
+            embedding.add_cw(v, w, previous);
+            previous = Some(w);
+        }
+        state.ordered_adjs[v] = ordered;
+    }
+
+    for &root in state.roots.iter() {
+        state.dfs_embedding(root, &mut embedding);
+    }
+
+    Some(PlanarEmbedding::from_rotation_system(
+        embedding.into_rotation_system(),
+    ))
+}
+
+/// Removes every edge of the slice whose removal keeps the graph non-planar.
+///
+/// The edges are removed block by block; a block that cannot be removed as a whole is split in halves, so only
+/// the edges of the final Kuratowski subgraph need individual planarity tests.
+///
+/// # Arguments
+/// * `h` - A mutable reference to a non-planar graph containing all edges of the slice.
+/// * `edges` - A slice of edges to be tested.
+fn reduce_to_kuratowski(h: &mut Graph, edges: &[(usize, usize)]) {
+    if edges.is_empty() {
+        return;
+    }
+
+    for &(u, v) in edges {
+        h.remove_edge(u, v);
+    }
+
+    if !h.is_planar() {
+        return;
+    }
+
+    for &(u, v) in edges {
+        h.add_edge(u, v);
+    }
+
+    if edges.len() == 1 {
+        return;
+    }
+
+    let (left, right) = edges.split_at(edges.len() / 2);
+    reduce_to_kuratowski(h, left);
+    reduce_to_kuratowski(h, right);
+}
+
+/// Finds a Kuratowski subgraph, i.e. a subdivision of K5 or K3,3, of a non-planar graph.
+///
+/// # Arguments
+/// * `g` - A reference to the input graph.
+///
+/// # Returns
+/// * `Some(Vec<(usize, usize)>)` containing the edges of the subdivision if the graph is non-planar, `None` otherwise.
+pub fn kuratowski_subgraph(g: &Graph) -> Option<Vec<(usize, usize)>> {
+    if g.is_planar() {
+        return None;
+    }
+
+    let mut h = g.clone();
+    let edges = g.all_edges();
+    reduce_to_kuratowski(&mut h, &edges);
+
+    Some(h.all_edges())
+}
+
+/// Adds edges to a planar graph until it becomes maximal planar.
+///
+/// The components are first joined into a single one, then every face of length greater than three is split by an
+/// edge between two vertices at distance two along its boundary, as long as such vertices are not adjacent yet.
+///
+/// # Arguments
+/// * `g` - A reference to the input graph.
+///
+/// # Returns
+/// * `Some((Graph, PlanarEmbedding))` containing the maximal planar supergraph and its embedding if the graph is
+///   planar, `None` otherwise.
+pub fn triangulate(g: &Graph) -> Option<(Graph, PlanarEmbedding)> {
+    let n = g.num_of_vertices();
+    let mut rotation = lr_planar_embedding(g)?.rotation_system().clone();
+    let mut h = g.clone();
+
+    // join the components, every face of one component may contain the other
+    let mut visited = vec![false; n];
+    let mut previous_root = None;
+    for root in 0..n {
+        if visited[root] {
+            continue;
+        }
+
+        visited[root] = true;
+        let mut stack = vec![root];
+        while let Some(v) = stack.pop() {
+            for &w in rotation[v].iter() {
+                if !visited[w] {
+                    visited[w] = true;
+                    stack.push(w);
+                }
+            }
+        }
+
+        if let Some(previous_root) = previous_root {
+            h.add_edge(previous_root, root);
+            rotation[previous_root].push(root);
+            rotation[root].push(previous_root);
+        }
+        previous_root = Some(root);
+    }
+
+    if n < 3 {
+        return Some((h, PlanarEmbedding::from_rotation_system(rotation)));
+    }
+
+    // split the faces, the faces of a single pass do not share any corner
+    loop {
+        let mut inserted = false;
+
+        for face in trace_faces(&rotation).iter().filter(|face| face.len() > 3) {
+            let k = face.len();
+            let corner = (0..k)
+                .map(|j| (face[j], face[(j + 1) % k], face[(j + 2) % k]))
+                .find(|&(a, _, c)| a != c && !h.has_edge(a, c));
+
+            if let Some((a, b, c)) = corner {
+                // the new face (a, b, c) requires c after b around a and a before b around c
+                let position = rotation[a].iter().position(|&w| w == b).unwrap();
+                rotation[a].insert(position + 1, c);
+                let position = rotation[c].iter().position(|&w| w == b).unwrap();
+                rotation[c].insert(position, a);
+                h.add_edge(a, c);
+                inserted = true;
+            }
+        }
+
+        if !inserted {
+            break;
+        }
+    }
+
+    Some((h, PlanarEmbedding::from_rotation_system(rotation)))
+}
+
+/// A struct representing a planar subgraph together with an embedding that is updated as edges are inserted.
+///
+/// An edge between vertices of different components, or of vertices lying on a common face, is inserted into the
+/// embedding directly. Only the remaining edges require the full planarity test, which also re-embeds the graph.
+#[derive(Clone, Debug)]
+pub struct IncrementalPlanarSubgraph {
+    graph: Graph,
+    rotation: Vec<Vec<usize>>,
+    parent: Vec<usize>,
+}
+
+impl IncrementalPlanarSubgraph {
+    /// Creates an incremental planar subgraph from a planar graph.
+    ///
+    /// # Arguments
+    /// * `g` - A reference to the planar graph.
+    ///
+    /// # Returns
+    /// * `Some(IncrementalPlanarSubgraph)` if the graph is planar, `None` otherwise.
+    pub fn new(g: &Graph) -> Option<Self> {
+        let rotation = lr_planar_embedding(g)?.rotation_system().clone();
+        let mut subgraph = IncrementalPlanarSubgraph {
+            graph: g.clone(),
+            rotation,
+            parent: (0..g.num_of_vertices()).collect(),
+        };
+
+        for (u, v) in g.all_edges() {
+            subgraph.union(u, v);
+        }
+
+        Some(subgraph)
+    }
+
+    /// Returns the planar subgraph.
+    ///
+    /// # Returns
+    /// * A reference to the graph.
+    pub fn graph(&self) -> &Graph {
+        &self.graph
+    }
+
+    /// Consumes the struct and returns the planar subgraph.
+    ///
+    /// # Returns
+    /// * The graph.
+    pub fn into_graph(self) -> Graph {
+        self.graph
+    }
+
+    /// Returns the current embedding of the planar subgraph.
+    ///
+    /// # Returns
+    /// * A planar embedding of the graph.
+    pub fn embedding(&self) -> PlanarEmbedding {
+        PlanarEmbedding::from_rotation_system(self.rotation.clone())
+    }
+
+    /// Finds the representative of the set containing a vertex, halving the path on the way.
+    ///
+    /// The sets are only merged, so after removals two vertices in different sets are in different components, but
+    /// not necessarily the other way round.
+    ///
+    /// # Arguments
+    /// * `v` - The index of the vertex.
+    ///
+    /// # Returns
+    /// * The index of the representative.
+    fn find(&mut self, mut v: usize) -> usize {
+        while self.parent[v] != v {
+            self.parent[v] = self.parent[self.parent[v]];
+            v = self.parent[v];
+        }
+        v
+    }
+
+    /// Merges the sets containing two vertices.
+    ///
+    /// # Arguments
+    /// * `u` - The index of the first vertex.
+    /// * `v` - The index of the second vertex.
+    fn union(&mut self, u: usize, v: usize) {
+        let (u, v) = (self.find(u), self.find(v));
+        self.parent[u] = v;
+    }
+
+    /// Returns the neighbor preceding a given one in the clockwise order around a vertex.
+    ///
+    /// # Arguments
+    /// * `vertex` - The index of the vertex.
+    /// * `neighbor` - The index of the neighbor.
+    ///
+    /// # Returns
+    /// * The index of the preceding neighbor.
+    fn predecessor(&self, vertex: usize, neighbor: usize) -> usize {
+        let order = &self.rotation[vertex];
+        let position = order.iter().position(|&w| w == neighbor).unwrap();
+        order[(position + order.len() - 1) % order.len()]
+    }
+
+    /// Finds corners of `u` and `v` on a common face.
+    ///
+    /// A corner is given by the neighbor the face arrives from. Inserting the new neighbor just before it in the
+    /// rotation places the edge inside the face.
+    ///
+    /// # Arguments
+    /// * `u` - The index of the first vertex.
+    /// * `v` - The index of the second vertex.
+    ///
+    /// # Returns
+    /// * `Some((usize, usize))` containing the neighbors of `u` and `v` the common face arrives from, or `None` if
+    ///   the vertices share no face.
+    fn common_face(&self, u: usize, v: usize) -> Option<(usize, usize)> {
+        let mut visited = HashSet::new();
+
+        for &a in self.rotation[u].iter() {
+            if !visited.insert((a, u)) {
+                continue;
+            }
+
+            let (mut from, mut to) = (u, self.predecessor(u, a));
+            while (from, to) != (a, u) {
+                visited.insert((from, to));
+                if to == v {
+                    return Some((a, from));
+                }
+                let next = self.predecessor(to, from);
+                (from, to) = (to, next);
+            }
+        }
+
+        None
+    }
+
+    /// Inserts an edge if it fits into the current embedding, i.e. its ends lie in different components or on a
+    /// common face.
+    ///
+    /// # Arguments
+    /// * `u` - The index of the first vertex.
+    /// * `v` - The index of the second vertex.
+    ///
+    /// # Returns
+    /// * `true` if the edge was inserted, `false` if it already exists or does not fit into the embedding.
+    pub fn try_add_edge_to_embedding(&mut self, u: usize, v: usize) -> bool {
+        if u == v || self.graph.has_edge(u, v) {
+            return false;
+        }
+
+        if self.find(u) != self.find(v) {
+            // any corners do, one component fits into every face of the other
+            self.rotation[u].push(v);
+            self.rotation[v].push(u);
+        } else if let Some((a, c)) = self.common_face(u, v) {
+            let position = self.rotation[u].iter().position(|&w| w == a).unwrap();
+            self.rotation[u].insert(position, v);
+            let position = self.rotation[v].iter().position(|&w| w == c).unwrap();
+            self.rotation[v].insert(position, u);
+        } else {
+            return false;
+        }
+
+        self.graph.add_edge(u, v);
+        self.union(u, v);
+        true
+    }
+
+    /// Inserts an edge if the subgraph stays planar.
+    ///
+    /// # Arguments
+    /// * `u` - The index of the first vertex.
+    /// * `v` - The index of the second vertex.
+    ///
+    /// # Returns
+    /// * `true` if the edge was inserted, `false` if it already exists or would break planarity.
+    pub fn try_add_edge(&mut self, u: usize, v: usize) -> bool {
+        if u == v || self.graph.has_edge(u, v) {
+            return false;
+        }
+
+        if self.try_add_edge_to_embedding(u, v) {
+            return true;
+        }
+
+        // the edge may still fit into another embedding
+        let mut h = self.graph.clone();
+        h.add_edge(u, v);
+        match lr_planar_embedding(&h) {
+            Some(embedding) => {
+                self.rotation = embedding.rotation_system().clone();
+                self.graph = h;
+                self.union(u, v);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Removes an edge from the subgraph and its embedding.
+    ///
+    /// # Arguments
+    /// * `u` - The index of the first vertex.
+    /// * `v` - The index of the second vertex.
+    ///
+    /// # Returns
+    /// * `true` if the edge was removed, `false` if it does not exist.
+    pub fn remove_edge(&mut self, u: usize, v: usize) -> bool {
+        if !self.graph.remove_edge(u, v) {
+            return false;
+        }
+
+        self.rotation[u].retain(|&w| w != v);
+        self.rotation[v].retain(|&w| w != u);
+        true
+    }
+}
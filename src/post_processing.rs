@@ -0,0 +1,293 @@
+use crate::constraints::{ConstraintError, EdgeConstraints};
+use crate::graphs::Graph;
+use crate::mps_alg::*;
+use crate::planarity::IncrementalPlanarSubgraph;
+use crate::weights::EdgeWeights;
+use std::time::{Duration, Instant};
+
+/// Sorts edges from the heaviest, keeping the lexicographic order among edges of equal weight.
+///
+/// # Arguments
+/// * `edges` - A mutable reference to the vector of edges.
+/// * `weights` - A reference to the weights of the edges.
+fn sort_by_weight(edges: &mut [(usize, usize)], weights: &EdgeWeights) {
+    edges.sort_unstable();
+    edges.sort_by(|&(a, b), &(c, d)| weights.weight(c, d).total_cmp(&weights.weight(a, b)));
+}
+
+/// Extends a planar subgraph to a maximal planar subgraph of the input graph.
+///
+/// The removed edges are tried from the heaviest and kept whenever the subgraph stays planar. The planarity of
+/// each insertion is checked incrementally against an embedding of the current subgraph.
+///
+/// # Arguments
+/// * `g` - A reference to the input graph.
+/// * `h` - A reference to a planar subgraph of the input graph.
+/// * `weights` - A reference to the weights of the edges.
+/// * `constraints` - A reference to the mandatory and forbidden edges, the forbidden ones are never added.
+///
+/// # Returns
+/// * `Some(Graph)` containing a maximal planar subgraph of the input graph that contains the given one, or `None`
+///   if the given subgraph is not planar.
+pub fn maximalize(
+    g: &Graph,
+    h: &Graph,
+    weights: &EdgeWeights,
+    constraints: &EdgeConstraints,
+) -> Option<Graph> {
+    let mut subgraph = IncrementalPlanarSubgraph::new(h)?;
+    let mut edges = g
+        .all_edges()
+        .into_iter()
+        .filter(|&(u, v)| !h.has_edge(u, v) && !constraints.is_forbidden(u, v))
+        .collect::<Vec<_>>();
+    sort_by_weight(&mut edges, weights);
+
+    for (u, v) in edges {
+        subgraph.try_add_edge(u, v);
+    }
+
+    Some(subgraph.into_graph())
+}
+
+/// Inserts the given edges into a planar subgraph as long as it stays planar.
+///
+/// # Arguments
+/// * `subgraph` - A mutable reference to the planar subgraph.
+/// * `edges` - A slice of edges to try, in order.
+fn extend(subgraph: &mut IncrementalPlanarSubgraph, edges: &[(usize, usize)]) {
+    for &(u, v) in edges {
+        subgraph.try_add_edge(u, v);
+    }
+}
+
+/// Swaps one edge of a maximal planar subgraph for two heavier removed edges, if such a swap is found.
+///
+/// The edges of the subgraph are removed one at a time, from the lightest, and the removed edges that become
+/// insertable are tried in pairs. Without a thorough search only the edges fitting into the current embedding
+/// count as insertable, which misses the swaps requiring a new embedding but avoids a planarity test per edge.
+///
+/// # Arguments
+/// * `subgraph` - A mutable reference to the maximal planar subgraph, extended again after a swap.
+/// * `candidates` - A slice of the edges that may be inserted, from the heaviest.
+/// * `weights` - A reference to the weights of the edges.
+/// * `constraints` - A reference to the mandatory and forbidden edges.
+/// * `thorough` - Whether to test the planarity of every edge instead of fitting it into the embedding.
+/// * `deadline` - The optional instant at which the search gives up.
+///
+/// # Returns
+/// * `true` if a swap was made, `false` otherwise.
+fn swap_edges(
+    subgraph: &mut IncrementalPlanarSubgraph,
+    candidates: &[(usize, usize)],
+    weights: &EdgeWeights,
+    constraints: &EdgeConstraints,
+    thorough: bool,
+    deadline: Option<Instant>,
+) -> bool {
+    let mut kept = subgraph
+        .graph()
+        .all_edges()
+        .into_iter()
+        .filter(|&(u, v)| !constraints.is_mandatory(u, v))
+        .collect::<Vec<_>>();
+    sort_by_weight(&mut kept, weights);
+    kept.reverse();
+
+    for (u, v) in kept {
+        if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            break;
+        }
+
+        let out_weight = weights.weight(u, v);
+        subgraph.remove_edge(u, v);
+
+        let mut insertable = Vec::new();
+        for &(x, y) in candidates.iter() {
+            if (x, y) == (u, v) || subgraph.graph().has_edge(x, y) {
+                continue;
+            }
+
+            let inserted = if thorough {
+                subgraph.try_add_edge(x, y)
+            } else {
+                subgraph.try_add_edge_to_embedding(x, y)
+            };
+            if inserted {
+                subgraph.remove_edge(x, y);
+                insertable.push((x, y));
+            }
+        }
+
+        for (i, &(a, b)) in insertable.iter().enumerate() {
+            subgraph.try_add_edge(a, b);
+
+            for &(c, d) in insertable[i + 1..].iter() {
+                if weights.weight(a, b) + weights.weight(c, d) > out_weight
+                    && subgraph.try_add_edge(c, d)
+                {
+                    extend(subgraph, candidates);
+                    return true;
+                }
+            }
+
+            subgraph.remove_edge(a, b);
+        }
+
+        subgraph.try_add_edge(u, v);
+    }
+
+    false
+}
+
+/// Improves a planar subgraph by swapping one of its edges for two removed edges.
+///
+/// The subgraph is maximalized first. Then swaps fitting into the current embedding are made while there are any,
+/// falling back to a thorough search testing the planarity of every edge, until no swap helps or the time limit
+/// runs out. Mandatory edges are never removed and forbidden edges never inserted.
+///
+/// # Arguments
+/// * `g` - A reference to the input graph.
+/// * `h` - A reference to a planar subgraph of the input graph.
+/// * `weights` - A reference to the weights of the edges.
+/// * `constraints` - A reference to the mandatory and forbidden edges.
+/// * `time_limit` - The optional wall-clock limit for the search.
+///
+/// # Returns
+/// * `Some((Graph, usize))` containing the improved maximal planar subgraph and the number of swaps made, or
+///   `None` if the given subgraph is not planar.
+pub fn local_search(
+    g: &Graph,
+    h: &Graph,
+    weights: &EdgeWeights,
+    constraints: &EdgeConstraints,
+    time_limit: Option<Duration>,
+) -> Option<(Graph, usize)> {
+    let deadline = time_limit.map(|limit| Instant::now() + limit);
+    let mut subgraph = IncrementalPlanarSubgraph::new(h)?;
+    let mut candidates = g
+        .all_edges()
+        .into_iter()
+        .filter(|&(u, v)| !constraints.is_forbidden(u, v))
+        .collect::<Vec<_>>();
+    sort_by_weight(&mut candidates, weights);
+    extend(&mut subgraph, &candidates);
+
+    let mut swaps = 0;
+    let mut thorough = false;
+
+    while deadline.is_none_or(|deadline| Instant::now() < deadline) {
+        if swap_edges(
+            &mut subgraph,
+            &candidates,
+            weights,
+            constraints,
+            thorough,
+            deadline,
+        ) {
+            swaps += 1;
+            thorough = false;
+        } else if !thorough {
+            thorough = true;
+        } else {
+            break;
+        }
+    }
+
+    Some((subgraph.into_graph(), swaps))
+}
+
+/// A struct representing an MPS algorithm followed by the maximalization of its planar subgraph.
+pub struct MaximalizedMps {
+    /// The algorithm computing the initial planar subgraph.
+    pub algorithm: Box<dyn MpsAlgorithm>,
+    /// The name reported for the maximalized algorithm.
+    pub name: &'static str,
+}
+
+impl MpsAlgorithm for MaximalizedMps {
+    /// Computes a planar subgraph with the wrapped algorithm and extends it to a maximal planar subgraph.
+    ///
+    /// # Arguments
+    /// * `g` - A reference to the input graph.
+    /// * `weights` - A reference to the weights of the edges.
+    /// * `constraints` - A reference to the mandatory and forbidden edges.
+    ///
+    /// # Returns
+    /// * `Ok(MpsResult)` containing the maximal subgraph, with the number of added edges under the
+    ///   `Maximalization` counter, or the `ConstraintError` if the mandatory edges cannot be kept.
+    fn solve_constrained(
+        &self,
+        g: &Graph,
+        weights: &EdgeWeights,
+        constraints: &EdgeConstraints,
+    ) -> Result<MpsResult, ConstraintError> {
+        let start = Instant::now();
+        let result = self.algorithm.solve_constrained(g, weights, constraints)?;
+        let mps = maximalize(g, &result.mps, weights, constraints)
+            .expect("MPS algorithms return planar subgraphs");
+        let added = mps.num_of_edges() - result.mps.num_of_edges();
+
+        let mut result = result.with_mps(g, weights, mps);
+        result.counters.insert("Maximalization", added);
+        result.wall_time = start.elapsed();
+
+        Ok(result)
+    }
+
+    /// Returns the name of the algorithm.
+    ///
+    /// # Returns
+    /// * A string slice representing the name of the algorithm.
+    fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+/// A struct representing an MPS algorithm followed by the local search improving its planar subgraph.
+pub struct LocalSearchMps {
+    /// The algorithm computing the initial planar subgraph.
+    pub algorithm: Box<dyn MpsAlgorithm>,
+    /// The name reported for the improved algorithm.
+    pub name: &'static str,
+    /// The optional wall-clock limit for the local search.
+    pub time_limit: Option<Duration>,
+}
+
+impl MpsAlgorithm for LocalSearchMps {
+    /// Computes a planar subgraph with the wrapped algorithm and improves it by edge swaps.
+    ///
+    /// # Arguments
+    /// * `g` - A reference to the input graph.
+    /// * `weights` - A reference to the weights of the edges.
+    /// * `constraints` - A reference to the mandatory and forbidden edges.
+    ///
+    /// # Returns
+    /// * `Ok(MpsResult)` containing the improved subgraph, with the number of swaps under the `Swaps` counter, or
+    ///   the `ConstraintError` if the mandatory edges cannot be kept.
+    fn solve_constrained(
+        &self,
+        g: &Graph,
+        weights: &EdgeWeights,
+        constraints: &EdgeConstraints,
+    ) -> Result<MpsResult, ConstraintError> {
+        let start = Instant::now();
+        let result = self.algorithm.solve_constrained(g, weights, constraints)?;
+        let (mps, swaps) = local_search(g, &result.mps, weights, constraints, self.time_limit)
+            .expect("MPS algorithms return planar subgraphs");
+
+        let mut result = result.with_mps(g, weights, mps);
+        result.counters.insert("Swaps", swaps);
+        result.wall_time = start.elapsed();
+
+        Ok(result)
+    }
+
+    /// Returns the name of the algorithm.
+    ///
+    /// # Returns
+    /// * A string slice representing the name of the algorithm.
+    fn name(&self) -> &'static str {
+        self.name
+    }
+}
@@ -1,9 +1,18 @@
 use std::collections::HashMap;
+use std::time::{Duration, Instant};
 
+use crate::constraints::{ConstraintError, EdgeConstraints};
+use crate::drawing::StraightLineDrawing;
 use crate::graphs::Graph;
+use crate::ilp::*;
 use crate::mps_alg::*;
+use crate::schnyder_wood::schnyder_wood;
+use crate::weights::EdgeWeights;
 use good_lp::*;
 
+/// Three orders of the vertices, each given as the vector of vertices from the smallest to the largest.
+pub type Orders = [Vec<usize>; 3];
+
 /// Computes the maximum planar subgraph using the Schnyder algorithm.
 ///
 /// # Arguments
@@ -12,6 +21,88 @@ use good_lp::*;
 /// # Returns
 /// * A `Graph` representing the maximum planar subgraph.
 pub fn schnyder_mps(g: &Graph) -> Graph {
+    schnyder_mps_with_time_limit(g, None).mps
+}
+
+/// Computes the maximum planar subgraph using the Schnyder algorithm, stopping the solver after the time limit.
+///
+/// # Arguments
+/// * `g` - A reference to the input graph.
+/// * `time_limit` - The optional wall-clock limit for the solver.
+///
+/// # Returns
+/// * The best planar subgraph found together with the proven upper bound and the gap.
+pub fn schnyder_mps_with_time_limit(g: &Graph, time_limit: Option<Duration>) -> IlpSolution {
+    let options = IlpOptions {
+        time_limit,
+        ..Default::default()
+    };
+    schnyder_mps_with_options(g, &options)
+        .expect("a program without fixed edges is always feasible")
+}
+
+/// Computes the maximum planar subgraph using the Schnyder algorithm, starting the solver from a planar subgraph.
+///
+/// The orders of the initial solution are derived from a Schnyder wood of a triangulation of the subgraph, so the
+/// solver starts with the subgraph as its incumbent.
+///
+/// # Arguments
+/// * `g` - A reference to the input graph.
+/// * `initial` - A reference to a planar subgraph of the input graph, e.g. computed by a heuristic.
+/// * `time_limit` - The optional wall-clock limit for the solver.
+///
+/// # Returns
+/// * The best planar subgraph found, never smaller than the initial one, together with the proven upper bound and
+///   the gap.
+pub fn schnyder_mps_with_initial_solution(
+    g: &Graph,
+    initial: &Graph,
+    time_limit: Option<Duration>,
+) -> IlpSolution {
+    let options = IlpOptions {
+        initial: Some(initial.clone()),
+        time_limit,
+        ..Default::default()
+    };
+    schnyder_mps_with_options(g, &options)
+        .expect("a program without fixed edges is always feasible")
+}
+
+/// Computes a maximum weight planar subgraph using the Schnyder algorithm.
+///
+/// An initial solution that is not a planar subgraph of `g` satisfying the constraints is ignored.
+///
+/// # Arguments
+/// * `g` - A reference to the input graph.
+/// * `options` - A reference to the weights of the edges, the fixed edges, the initial solution and the time limit.
+///
+/// # Returns
+/// * `Ok(IlpSolution)` containing the best planar subgraph found, never lighter than the initial one, together with
+///   the proven upper bound and the gap, or the `ConstraintError` if no planar subgraph satisfies the constraints.
+pub fn schnyder_mps_with_options(
+    g: &Graph,
+    options: &IlpOptions,
+) -> Result<IlpSolution, ConstraintError> {
+    Ok(schnyder_orders_with_options(g, options)?.0)
+}
+
+/// Solves the Schnyder program and extracts the three orders of the best solution.
+///
+/// # Arguments
+/// * `g` - A reference to the input graph.
+/// * `options` - A reference to the weights of the edges, the fixed edges, the initial solution and the time limit.
+///
+/// # Returns
+/// * `Ok((IlpSolution, Option<Orders>))` containing the solution and the orders `t` of the solver, or the
+///   `ConstraintError` if no planar subgraph satisfies the constraints. The orders are `None` if the solver found
+///   no solution or the initial subgraph was kept instead of it.
+fn schnyder_orders_with_options(
+    g: &Graph,
+    options: &IlpOptions,
+) -> Result<(IlpSolution, Option<Orders>), ConstraintError> {
+    options.constraints.validate(g)?;
+    let weights = &options.weights;
+    let initial = options.valid_initial(g);
     let mut vars = ProblemVariables::new();
 
     let n = g.num_of_vertices();
@@ -48,12 +139,10 @@ pub fn schnyder_mps(g: &Graph) -> Graph {
         }
     }
 
-    // Define the objective function to maximize the number of selected edges
-    let mut objective = Expression::from(0);
-    for e in edges.iter() {
-        objective += s[e];
-    }
+    // Define the objective function to maximize the total weight of the selected edges
+    let objective = weighted_sum(&edges, &s, weights);
 
+    let columns = column_indices(&vars);
     let mut problem = vars.maximise(objective).using(highs);
 
     // Apply the Euler criterion
@@ -129,32 +218,273 @@ pub fn schnyder_mps(g: &Graph) -> Graph {
         }
     }
 
-    let solution = problem.solve().unwrap();
+    problem = fix_edges(problem, &s, &options.constraints);
 
-    let mut mps = Graph::empty(n);
+    let mut initial_solution = HashMap::new();
 
-    for &e in edges.iter() {
-        if solution.value(s[&e]) >= 0.5 {
-            mps.add_edge(e.0, e.1);
+    if let Some(h) = initial {
+        select_edges(&mut initial_solution, &s, h);
+
+        if let Some(wood) = schnyder_wood(h) {
+            for (i, order) in wood.orders().iter().enumerate() {
+                let mut rank = vec![0; n];
+                for (r, &v) in order.iter().enumerate() {
+                    rank[v] = r;
+                }
+
+                for u in 0..n {
+                    for v in 0..n {
+                        let value = if u != v && rank[u] < rank[v] {
+                            1.0
+                        } else {
+                            0.0
+                        };
+                        initial_solution.insert(t[i][u][v], value);
+                    }
+                }
+
+                for v in 0..n {
+                    for e in edges.iter() {
+                        let below =
+                            v != e.0 && v != e.1 && rank[e.0] < rank[v] && rank[e.1] < rank[v];
+                        let value = if below { 1.0 } else { 0.0 };
+                        initial_solution.insert(a[i][v][e], value);
+                    }
+                }
+            }
+        }
+    }
+
+    let SolverOutcome {
+        mut mps,
+        dual_bound,
+        time_limited,
+        values,
+        ..
+    } = solve_with_time_limit(
+        g,
+        weights,
+        problem,
+        &columns,
+        &s,
+        &initial_solution,
+        options.time_limit,
+    );
+
+    // t_i,u,v = 1 iff u <_i v, so the rank of v in the i-th order is the number of vertices below it
+    let mut orders = values.map(|values| {
+        [0, 1, 2].map(|i| {
+            let mut order = (0..n).collect::<Vec<usize>>();
+            order.sort_by_key(|&v| {
+                (0..n)
+                    .filter(|&u| u != v && values[columns[&t[i][u][v]]] >= 0.5)
+                    .count()
+            });
+            order
+        })
+    });
+
+    if let Some(h) = initial {
+        if weights.total_weight(h) > weights.total_weight(&mps) {
+            mps = h.clone();
+            orders = None;
+        }
+    }
+
+    Ok((
+        IlpSolution::new(
+            g,
+            weights,
+            &options.constraints,
+            mps,
+            dual_bound,
+            time_limited,
+        ),
+        orders,
+    ))
+}
+
+/// A maximum planar subgraph found by the Schnyder algorithm together with a straight-line drawing of it.
+#[derive(Clone, Debug)]
+pub struct SchnyderDrawing {
+    /// The solution of the integer program.
+    pub solution: IlpSolution,
+    /// Three orders of the vertices such that for every edge of the subgraph and every other vertex, both ends of the
+    /// edge precede the vertex in at least one of the orders.
+    pub orders: Orders,
+    /// The grid point of every vertex in a plane straight-line drawing of the subgraph.
+    pub coordinates: Vec<(usize, usize)>,
+    /// Whether the orders of the solver were replaced by those of a Schnyder wood, because they did not satisfy the
+    /// condition or their projection was not plane.
+    pub fallback: bool,
+}
+
+impl SchnyderDrawing {
+    /// Returns the straight-line drawing of the subgraph.
+    ///
+    /// # Returns
+    /// * The drawing with the vertices at their grid points.
+    pub fn drawing(&self) -> StraightLineDrawing {
+        StraightLineDrawing::from_grid(&self.coordinates)
+    }
+
+    /// Formats the drawing of the subgraph as SVG.
+    ///
+    /// # Returns
+    /// * The SVG document.
+    pub fn to_svg(&self) -> String {
+        self.drawing().to_svg(&self.solution.mps, &[])
+    }
+}
+
+/// Computes the rank of every vertex in each of three orders.
+///
+/// # Arguments
+/// * `orders` - A reference to the orders.
+///
+/// # Returns
+/// * A vector of the three ranks of every vertex.
+pub(crate) fn ranks(orders: &Orders) -> Vec<[usize; 3]> {
+    let mut ranks = vec![[0; 3]; orders[0].len()];
+    for (i, order) in orders.iter().enumerate() {
+        for (r, &v) in order.iter().enumerate() {
+            ranks[v][i] = r;
+        }
+    }
+    ranks
+}
+
+/// Checks if three orders satisfy the Schnyder condition for a graph.
+///
+/// # Arguments
+/// * `g` - A reference to the graph.
+/// * `orders` - A reference to the orders.
+///
+/// # Returns
+/// * `true` if for every edge and every other vertex, both ends of the edge precede the vertex in one of the
+///   orders, `false` otherwise.
+fn is_realizer(g: &Graph, orders: &Orders) -> bool {
+    let rank = ranks(orders);
+
+    g.all_edges().into_iter().all(|(u, v)| {
+        (0..g.num_of_vertices())
+            .filter(|&w| w != u && w != v)
+            .all(|w| (0..3).any(|i| rank[u][i] < rank[w][i] && rank[v][i] < rank[w][i]))
+    })
+}
+
+/// Computes a straight-line drawing of a planar subgraph derived from the three orders of the Schnyder program.
+///
+/// Every vertex is placed at its vector of ranks projected along the diagonal `(1, 1, 1)`. The projection is not
+/// plane for every realizer, so it is checked. If it has crossings or there are no orders, the vertices are placed
+/// at the coordinates of a Schnyder wood of a triangulation of the subgraph instead, which always give a plane
+/// drawing on the `(2n - 5) x (2n - 5)` grid, and the orders are replaced by the ones induced by these coordinates.
+///
+/// # Arguments
+/// * `mps` - A reference to the planar subgraph.
+/// * `orders` - The orders of the solver if they satisfy the Schnyder condition for the subgraph.
+///
+/// # Returns
+/// * A tuple of the orders, the grid point of every vertex and whether the orders were replaced.
+pub(crate) fn draw_subgraph(
+    mps: &Graph,
+    orders: Option<Orders>,
+) -> (Orders, Vec<(usize, usize)>, bool) {
+    let n = mps.num_of_vertices();
+
+    if let Some(orders) = orders {
+        let coordinates = ranks(&orders)
+            .into_iter()
+            .map(|[r0, r1, r2]| (r0 + n - 1 - r2, r1 + n - 1 - r2))
+            .collect::<Vec<_>>();
+
+        if StraightLineDrawing::from_grid(&coordinates).is_plane(mps) {
+            return (orders, coordinates, false);
         }
     }
 
-    mps
+    match schnyder_wood(mps) {
+        Some(wood) => {
+            let coordinates = wood.coordinates().iter().map(|c| (c[0], c[1])).collect();
+            (wood.orders(), coordinates, true)
+        }
+        // at most two vertices, every order realizes the graph
+        None => (
+            [(0..n).collect(), (0..n).collect(), (0..n).rev().collect()],
+            (0..n).map(|v| (v, 0)).collect(),
+            true,
+        ),
+    }
+}
+
+/// Computes a maximum weight planar subgraph using the Schnyder algorithm, together with a straight-line drawing.
+///
+/// The drawing is derived from the three orders found by the solver, so no separate planarity embedder is needed
+/// whenever their projection is plane. Otherwise the drawing and the orders come from a Schnyder wood, which the
+/// `fallback` flag of the result reports.
+///
+/// # Arguments
+/// * `g` - A reference to the input graph.
+/// * `options` - A reference to the weights of the edges, the fixed edges, the initial solution and the time limit.
+///
+/// # Returns
+/// * `Ok(SchnyderDrawing)` containing the solution, the orders, the grid point of every vertex and whether the
+///   fallback was used, or the `ConstraintError` if no planar subgraph satisfies the constraints.
+pub fn schnyder_drawing_with_options(
+    g: &Graph,
+    options: &IlpOptions,
+) -> Result<SchnyderDrawing, ConstraintError> {
+    let (solution, orders) = schnyder_orders_with_options(g, options)?;
+    let orders = orders.filter(|orders| is_realizer(&solution.mps, orders));
+    let (orders, coordinates, fallback) = draw_subgraph(&solution.mps, orders);
+
+    Ok(SchnyderDrawing {
+        solution,
+        orders,
+        coordinates,
+        fallback,
+    })
 }
 
 /// A struct representing the Schnyder MPS algorithm.
-pub struct SchnyderMps {}
+#[derive(Default)]
+pub struct SchnyderMps {
+    /// The optional wall-clock limit for the solver.
+    pub time_limit: Option<Duration>,
+    /// The optional heuristic whose solution is used as the initial solution.
+    pub warm_start: Option<Box<dyn MpsAlgorithm>>,
+}
 
 impl MpsAlgorithm for SchnyderMps {
-    /// Computes the maximum planar subgraph using the Schnyder algorithm.
+    /// Computes a maximum weight planar subgraph satisfying the fixed-edge constraints using the Schnyder algorithm.
     ///
     /// # Arguments
     /// * `g` - A reference to the input graph.
+    /// * `weights` - A reference to the weights of the edges.
+    /// * `constraints` - A reference to the mandatory and forbidden edges.
     ///
     /// # Returns
-    /// * A `Graph` representing the maximum planar subgraph.
-    fn maximum_planar_subgraph(&self, g: &Graph) -> Graph {
-        schnyder_mps(g)
+    /// * `Ok(MpsResult)` containing the subgraph, its status and the upper bound, or the `ConstraintError` if no
+    ///   planar subgraph satisfies the constraints.
+    fn solve_constrained(
+        &self,
+        g: &Graph,
+        weights: &EdgeWeights,
+        constraints: &EdgeConstraints,
+    ) -> Result<MpsResult, ConstraintError> {
+        let start = Instant::now();
+        let options = IlpOptions {
+            weights: weights.clone(),
+            constraints: constraints.clone(),
+            initial: match &self.warm_start {
+                Some(heuristic) => Some(heuristic.solve_constrained(g, weights, constraints)?.mps),
+                None => None,
+            },
+            time_limit: self.time_limit,
+        };
+        let solution = schnyder_mps_with_options(g, &options)?;
+
+        Ok(MpsResult::exact(g, solution, start.elapsed()))
     }
 
     /// Returns the name of the algorithm.
@@ -0,0 +1,197 @@
+use crate::graphs::Graph;
+use crate::planarity::{triangulate, PlanarEmbedding};
+use std::collections::{HashMap, HashSet};
+
+/// A struct representing a Schnyder wood of a maximal planar graph.
+///
+/// The inner edges are partitioned into three trees. The `i`-th tree is rooted at the `i`-th outer vertex and
+/// every inner vertex has exactly one parent in each of the trees.
+#[derive(Clone, Debug)]
+pub struct SchnyderWood {
+    outer: [usize; 3],
+    parents: [Vec<Option<usize>>; 3],
+    faces: Vec<Vec<usize>>,
+}
+
+impl SchnyderWood {
+    /// Computes a Schnyder wood of a maximal planar graph from a canonical ordering.
+    ///
+    /// The canonical ordering is built in reverse by repeatedly removing a vertex without chords from the outer
+    /// path. The removed vertex gets the ends of its neighborhood on the path as parents in the first two trees and
+    /// becomes the parent in the third tree of the vertices it covered.
+    ///
+    /// # Arguments
+    /// * `embedding` - A reference to an embedding whose faces are all triangles; the first face is the outer one.
+    ///
+    /// # Returns
+    /// * `Some(SchnyderWood)` if the embedding is a triangulation with at least three vertices, `None` otherwise.
+    pub fn new(embedding: &PlanarEmbedding) -> Option<Self> {
+        let rotation = embedding.rotation_system();
+        let n = rotation.len();
+        let faces = embedding.faces();
+
+        if n < 3 || faces.iter().any(|face| face.len() != 3) || faces.len() != 2 * n - 4 {
+            return None;
+        }
+
+        let outer = [faces[0][0], faces[0][1], faces[0][2]];
+        let mut parents = [vec![None; n], vec![None; n], vec![None; n]];
+        let mut on_path = vec![false; n];
+        let mut path = vec![outer[0], outer[2], outer[1]];
+        for &v in path.iter() {
+            on_path[v] = true;
+        }
+
+        while path.len() > 2 {
+            let j = (1..path.len() - 1).find(|&j| {
+                rotation[path[j]]
+                    .iter()
+                    .all(|&w| !on_path[w] || w == path[j - 1] || w == path[j + 1])
+            })?;
+            let (left, v, right) = (path[j - 1], path[j], path[j + 1]);
+
+            // the neighbors below v follow the right neighbor in the clockwise order around v
+            let order = &rotation[v];
+            let start = order.iter().position(|&w| w == right)?;
+            let mut covered = (1..order.len())
+                .map(|k| order[(start + k) % order.len()])
+                .take_while(|&w| w != left)
+                .collect::<Vec<usize>>();
+            covered.reverse();
+
+            if v != outer[2] {
+                parents[0][v] = Some(left);
+                parents[1][v] = Some(right);
+            }
+            for &w in covered.iter() {
+                parents[2][w] = Some(v);
+                on_path[w] = true;
+            }
+            on_path[v] = false;
+
+            path.splice(j..j + 1, covered);
+        }
+
+        Some(SchnyderWood {
+            outer,
+            parents,
+            faces: faces[1..].to_vec(),
+        })
+    }
+
+    /// Returns the outer vertices, the `i`-th of them being the root of the `i`-th tree.
+    ///
+    /// # Returns
+    /// * An array of the three outer vertices.
+    pub fn outer_vertices(&self) -> [usize; 3] {
+        self.outer
+    }
+
+    /// Returns the parent of a vertex in one of the trees.
+    ///
+    /// # Arguments
+    /// * `tree` - The index of the tree, between 0 and 2.
+    /// * `vertex` - The index of the vertex.
+    ///
+    /// # Returns
+    /// * `Some(usize)` containing the parent if the vertex is an inner vertex, `None` otherwise.
+    pub fn parent(&self, tree: usize, vertex: usize) -> Option<usize> {
+        self.parents[tree][vertex]
+    }
+
+    /// Computes the Schnyder coordinates of the vertices.
+    ///
+    /// The `i`-th coordinate of an inner vertex is the number of inner faces in the region bounded by its paths in
+    /// the two other trees and the outer edge opposite to the `i`-th outer vertex. The coordinates of every vertex
+    /// sum up to `2n - 5`, the number of inner faces.
+    ///
+    /// # Returns
+    /// * A vector of the three coordinates of every vertex.
+    pub fn coordinates(&self) -> Vec<[usize; 3]> {
+        let n = self.parents[0].len();
+        let num_of_faces = self.faces.len();
+
+        let mut face_of_arc = HashMap::with_capacity(3 * num_of_faces);
+        for (f, face) in self.faces.iter().enumerate() {
+            for j in 0..3 {
+                face_of_arc.insert((face[j], face[(j + 1) % 3]), f);
+            }
+        }
+
+        let mut coordinates = vec![[0; 3]; n];
+        for i in 0..3 {
+            coordinates[self.outer[i]][i] = num_of_faces;
+        }
+
+        for v in (0..n).filter(|v| !self.outer.contains(v)) {
+            let mut path_edges = HashSet::new();
+            for tree in 0..3 {
+                let mut u = v;
+                while let Some(parent) = self.parents[tree][u] {
+                    path_edges.insert((u.min(parent), u.max(parent)));
+                    u = parent;
+                }
+            }
+
+            for (i, coordinate) in coordinates[v].iter_mut().enumerate() {
+                let arc = (self.outer[(i + 2) % 3], self.outer[(i + 1) % 3]);
+                let seed = face_of_arc[&arc];
+                let mut visited = vec![false; num_of_faces];
+                visited[seed] = true;
+                let mut stack = vec![seed];
+                let mut count = 0;
+
+                while let Some(f) = stack.pop() {
+                    count += 1;
+                    let face = &self.faces[f];
+                    for j in 0..3 {
+                        let (x, y) = (face[j], face[(j + 1) % 3]);
+                        if path_edges.contains(&(x.min(y), x.max(y))) {
+                            continue;
+                        }
+                        if let Some(&neighbor) = face_of_arc.get(&(y, x)) {
+                            if !visited[neighbor] {
+                                visited[neighbor] = true;
+                                stack.push(neighbor);
+                            }
+                        }
+                    }
+                }
+
+                *coordinate = count;
+            }
+        }
+
+        coordinates
+    }
+
+    /// Computes the three linear orders of the vertices induced by the Schnyder coordinates.
+    ///
+    /// The `i`-th order compares the `i`-th coordinates and breaks ties by the next coordinate. For every edge and
+    /// every other vertex, both ends of the edge precede the vertex in at least one of the orders.
+    ///
+    /// # Returns
+    /// * An array of the three orders, each given as the vector of vertices from the smallest to the largest.
+    pub fn orders(&self) -> [Vec<usize>; 3] {
+        let coordinates = self.coordinates();
+        let n = coordinates.len();
+
+        [0, 1, 2].map(|i| {
+            let mut order = (0..n).collect::<Vec<usize>>();
+            order.sort_by_key(|&v| (coordinates[v][i], coordinates[v][(i + 1) % 3]));
+            order
+        })
+    }
+}
+
+/// Computes a Schnyder wood of a maximal planar supergraph of a planar graph.
+///
+/// # Arguments
+/// * `g` - A reference to the input graph.
+///
+/// # Returns
+/// * `Some(SchnyderWood)` if the graph is planar and has at least three vertices, `None` otherwise.
+pub fn schnyder_wood(g: &Graph) -> Option<SchnyderWood> {
+    let (_, embedding) = triangulate(g)?;
+    SchnyderWood::new(&embedding)
+}
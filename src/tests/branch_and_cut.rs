@@ -0,0 +1,76 @@
+use crate::branch_and_cut::{
+    branch_and_cut_mps, branch_and_cut_mps_with_options, branch_and_cut_mps_with_time_limit,
+    BranchAndCutMps,
+};
+use crate::graphs::Graph;
+use crate::ilp::IlpOptions;
+use crate::mps_alg::{MpsAlgorithm, MpsStatus};
+use crate::rand_graphs::bliztstein_generation;
+use crate::schnyder::schnyder_mps;
+use crate::weights::EdgeWeights;
+use std::time::Duration;
+
+#[test]
+fn branch_and_cut_mps_complete_test() {
+    for n in 3..8 {
+        let graph = Graph::complete(n);
+        let mps = branch_and_cut_mps(&graph);
+        assert_eq!(mps.num_of_vertices(), n);
+        assert_eq!(mps.num_of_edges(), 3 * n - 6);
+        assert!(mps.is_planar());
+    }
+}
+
+#[test]
+fn branch_and_cut_mps_3_regular_test() {
+    for n in [8, 10] {
+        let degs = vec![3; n];
+        let graph = bliztstein_generation(&degs).unwrap();
+        let mps = branch_and_cut_mps(&graph);
+        assert!(mps.is_planar());
+        assert_eq!(mps.num_of_edges(), schnyder_mps(&graph).num_of_edges());
+    }
+}
+
+#[test]
+fn branch_and_cut_mps_time_limit_test() {
+    let degs = vec![3; 100];
+    let graph = bliztstein_generation(&degs).unwrap();
+    let solution = branch_and_cut_mps_with_time_limit(&graph, Some(Duration::from_millis(100)));
+    assert!(solution.time_limited);
+    assert!(solution.mps.is_planar());
+    assert!(solution.weight <= solution.upper_bound);
+    assert!(solution.upper_bound <= graph.num_of_edges() as f64);
+}
+
+#[test]
+fn branch_and_cut_mps_weighted_test() {
+    let graph = Graph::complete(5);
+    let mut weights = EdgeWeights::new();
+    for v in 1..5 {
+        weights.set_weight(0, v, 0.5);
+    }
+    weights.set_weight(1, 2, 2.5);
+
+    let options = IlpOptions {
+        weights,
+        ..Default::default()
+    };
+    let solution = branch_and_cut_mps_with_options(&graph, &options).unwrap();
+    assert!(solution.is_optimal());
+    assert!(solution.mps.is_planar());
+    assert_eq!(solution.weight, 9.0);
+    assert_eq!(solution.upper_bound, 9.0);
+    assert_eq!(solution.mps.degree(0), 3);
+}
+
+#[test]
+fn branch_and_cut_mps_empty_test() {
+    for n in 0..4 {
+        let graph = Graph::empty(n);
+        let result = BranchAndCutMps::default().solve(&graph);
+        assert_eq!(result.mps.num_of_vertices(), n);
+        assert_eq!(result.mps.num_of_edges(), 0);
+        assert_eq!(result.status, MpsStatus::Optimal);
+    }
+}
@@ -0,0 +1,198 @@
+use crate::branch_and_cut::{branch_and_cut_mps_with_options, BranchAndCutMps};
+use crate::constraints::{ConstraintError, EdgeConstraints};
+use crate::facial_walks::{facial_walks_mps_with_options, FacialWalksMps};
+use crate::graphs::Graph;
+use crate::ilp::IlpOptions;
+use crate::match_merge::*;
+use crate::mps_alg::{MpsAlgorithm, MpsStatus};
+use crate::schnyder::{schnyder_mps_with_options, SchnyderMps};
+use crate::weights::EdgeWeights;
+use std::time::Duration;
+
+#[test]
+fn validate_constraints_test() {
+    let graph = Graph::complete(6);
+
+    let mut constraints = EdgeConstraints::new();
+    constraints.require(1, 0);
+    constraints.forbid(2, 3);
+    assert!(constraints.is_mandatory(0, 1));
+    assert!(constraints.is_forbidden(3, 2));
+    assert_eq!(
+        constraints.validate(&graph).unwrap().all_edges(),
+        vec![(0, 1)]
+    );
+
+    constraints.require(0, 6);
+    assert_eq!(
+        constraints.validate(&graph),
+        Err(ConstraintError::MissingEdge(0, 6))
+    );
+
+    let mut constraints = EdgeConstraints::new();
+    constraints.require(2, 3);
+    constraints.forbid(3, 2);
+    assert_eq!(
+        constraints.validate(&graph),
+        Err(ConstraintError::Conflict(2, 3))
+    );
+
+    let mut constraints = EdgeConstraints::new();
+    for (u, v) in Graph::complete(5).all_edges() {
+        constraints.require(u, v);
+    }
+    match constraints.validate(&graph) {
+        Err(ConstraintError::NonPlanarMandatory(k)) => assert_eq!(k.len(), 10),
+        other => panic!("expected a non-planar mandatory set, got {:?}", other),
+    }
+}
+
+#[test]
+fn match_merge_constrained_test() {
+    let graph = Graph::complete(8);
+    let mut constraints = EdgeConstraints::new();
+    for v in 1..8 {
+        constraints.require(v - 1, v);
+    }
+    constraints.forbid(0, 2);
+    constraints.forbid(5, 7);
+
+    let algorithms: Vec<Box<dyn MpsAlgorithm>> = vec![
+        Box::new(CalinescuMps {}),
+        Box::new(SchmidMps {}),
+        Box::new(MyMps {}),
+        Box::new(PoranenMps {}),
+    ];
+
+    for alg in algorithms.iter() {
+        let result = alg
+            .solve_constrained(&graph, &EdgeWeights::new(), &constraints)
+            .unwrap();
+        assert!(result.mps.is_planar());
+        assert!((1..8).all(|v| result.mps.has_edge(v - 1, v)));
+        assert!(!result.mps.has_edge(0, 2));
+        assert!(!result.mps.has_edge(5, 7));
+    }
+}
+
+#[test]
+fn exact_constrained_test() {
+    let graph = Graph::complete(5);
+    let mut constraints = EdgeConstraints::new();
+    constraints.forbid(0, 1);
+    constraints.forbid(2, 3);
+
+    let algorithms: Vec<Box<dyn MpsAlgorithm>> = vec![
+        Box::new(SchnyderMps::default()),
+        Box::new(BranchAndCutMps::default()),
+    ];
+
+    for alg in algorithms.iter() {
+        let result = alg
+            .solve_constrained(&graph, &EdgeWeights::new(), &constraints)
+            .unwrap();
+        assert_eq!(result.status, MpsStatus::Optimal);
+        assert_eq!(result.mps.num_of_edges(), 8);
+        assert!(!result.mps.has_edge(0, 1));
+        assert!(!result.mps.has_edge(2, 3));
+    }
+}
+
+#[test]
+fn bridge_constrained_test() {
+    let graph = Graph::complete(3);
+    let mut forbidden = EdgeConstraints::new();
+    forbidden.forbid(1, 2);
+    let mut mixed = forbidden.clone();
+    mixed.require(0, 1);
+
+    let algorithms: Vec<Box<dyn MpsAlgorithm>> = vec![
+        Box::new(SchnyderMps::default()),
+        Box::new(BranchAndCutMps::default()),
+        Box::new(FacialWalksMps::default()),
+    ];
+
+    for constraints in [forbidden, mixed] {
+        for alg in algorithms.iter() {
+            let result = alg
+                .solve_constrained(&graph, &EdgeWeights::new(), &constraints)
+                .unwrap();
+            assert_eq!(result.status, MpsStatus::Optimal);
+            assert_eq!(result.mps.num_of_edges(), 2);
+            assert!(result.mps.has_edge(0, 1) && result.mps.has_edge(0, 2));
+            assert_eq!(result.weight, 2.0);
+        }
+    }
+}
+
+#[test]
+fn non_planar_mandatory_test() {
+    let graph = Graph::complete(6);
+    let mut constraints = EdgeConstraints::new();
+    for (u, v) in Graph::bipartite_complete(3, 3).all_edges() {
+        constraints.require(u, v);
+    }
+
+    let algorithms: Vec<Box<dyn MpsAlgorithm>> = vec![
+        Box::new(CalinescuMps {}),
+        Box::new(SchnyderMps::default()),
+        Box::new(BranchAndCutMps::default()),
+    ];
+
+    for alg in algorithms.iter() {
+        let result = alg.solve_constrained(&graph, &EdgeWeights::new(), &constraints);
+        assert!(matches!(
+            result,
+            Err(ConstraintError::NonPlanarMandatory(_))
+        ));
+    }
+}
+
+#[test]
+fn invalid_initial_solution_test() {
+    let graph = Graph::complete(5);
+    let mut weights = EdgeWeights::new();
+    weights.set_weight(0, 1, 100.0);
+    let mut constraints = EdgeConstraints::new();
+    constraints.forbid(0, 1);
+
+    let mut forbidden_edge = Graph::complete(5);
+    forbidden_edge.remove_edge(2, 3);
+    let solvers = [
+        branch_and_cut_mps_with_options as fn(&Graph, &IlpOptions) -> _,
+        schnyder_mps_with_options,
+        facial_walks_mps_with_options,
+    ];
+
+    for initial in [Graph::complete(5), forbidden_edge] {
+        let options = IlpOptions {
+            weights: weights.clone(),
+            constraints: constraints.clone(),
+            initial: Some(initial),
+            time_limit: None,
+        };
+
+        for solve in solvers {
+            let solution = solve(&graph, &options).unwrap();
+            assert!(solution.mps.is_planar());
+            assert!(!solution.mps.has_edge(0, 1));
+            assert_eq!(solution.mps.num_of_edges(), 9);
+        }
+    }
+}
+
+#[test]
+fn forbidden_upper_bound_test() {
+    let graph = Graph::complete(3);
+    let mut constraints = EdgeConstraints::new();
+    constraints.forbid(1, 2);
+
+    let options = IlpOptions {
+        constraints,
+        time_limit: Some(Duration::ZERO),
+        ..Default::default()
+    };
+    let solution = branch_and_cut_mps_with_options(&graph, &options).unwrap();
+    assert!(solution.time_limited);
+    assert_eq!(solution.upper_bound, 2.0);
+}
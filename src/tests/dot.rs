@@ -0,0 +1,31 @@
+use crate::graphs::Graph;
+use crate::match_merge::*;
+use crate::mps_alg::MpsAlgorithm;
+
+#[test]
+fn dot_test() {
+    let mut graph = Graph::complete(3);
+    graph.add_vertex();
+    let mut mps = graph.clone();
+    mps.remove_edge(0, 2);
+
+    let dot = graph.to_dot(Some(&mps), Some(&[4, 4, 2, 3]));
+    assert!(dot.starts_with("graph G {\n"));
+    assert!(dot.contains("  0 [fillcolor=5];\n  1 [fillcolor=5];\n  2;\n  3;\n"));
+    assert!(dot.contains("  0 -- 1;\n"));
+    assert!(dot.contains("  0 -- 2 [style=dashed, color=red];\n"));
+    assert!(!graph.to_dot(None, None).contains("dashed"));
+}
+
+#[test]
+fn match_merge_components_test() {
+    let graph = Graph::complete(7);
+    let result = SchmidMps {}.solve(&graph);
+    let components = result.components.as_ref().unwrap();
+    assert_eq!(components.len(), 7);
+    assert_eq!(components[0], 0);
+
+    let dot = graph.to_dot(Some(&result.mps), Some(components));
+    assert_eq!(dot.matches("dashed").count(), result.removed_edges.len());
+    assert_eq!(dot.matches(" -- ").count(), graph.num_of_edges());
+}
@@ -0,0 +1,121 @@
+use crate::drawing::{Layout, StraightLineDrawing, TikzOptions, VertexLabels};
+use crate::graphs::Graph;
+use crate::match_merge::SchmidMps;
+use crate::mps_alg::MpsAlgorithm;
+use crate::rand_graphs::general_random_graph;
+
+#[test]
+fn is_plane_test() {
+    let graph = Graph::complete(4);
+    let plane = StraightLineDrawing::from_grid(&[(0, 0), (4, 0), (2, 4), (2, 1)]);
+    assert!(plane.is_plane(&graph));
+
+    let crossing = StraightLineDrawing::from_grid(&[(0, 0), (4, 0), (4, 4), (0, 4)]);
+    assert!(!crossing.is_plane(&graph));
+
+    let mut path = Graph::empty(3);
+    path.add_edge(0, 2);
+    let through_vertex = StraightLineDrawing::from_grid(&[(0, 0), (1, 1), (2, 2)]);
+    assert!(!through_vertex.is_plane(&path));
+    path.remove_edge(0, 2);
+    path.add_edge(0, 1);
+    path.add_edge(1, 2);
+    assert!(through_vertex.is_plane(&path));
+
+    let coincident = StraightLineDrawing::from_grid(&[(0, 0), (0, 0), (1, 0)]);
+    assert!(!coincident.is_plane(&Graph::empty(3)));
+}
+
+#[test]
+fn svg_test() {
+    let graph = Graph::complete(3);
+    let drawing = StraightLineDrawing::from_grid(&[(0, 0), (2, 0), (1, 1)]);
+    let svg = drawing.to_svg(&graph, &[]);
+    assert!(svg.starts_with("<svg"));
+    assert!(svg.contains("width=\"120\" height=\"80\""));
+    assert!(svg.contains("<line x1=\"20\" y1=\"60\" x2=\"100\" y2=\"60\"/>"));
+    assert_eq!(svg.matches("<circle").count(), 3);
+
+    let filename = std::env::temp_dir().join("svg_test.svg");
+    let filename = filename.to_str().unwrap();
+    drawing.write_svg(&graph, &[], filename).unwrap();
+    assert_eq!(std::fs::read_to_string(filename).unwrap(), svg);
+}
+
+#[test]
+fn layouts_test() {
+    for layout in [Layout::Tutte, Layout::Schnyder] {
+        for n in [1, 2, 3, 10, 40] {
+            let graph = general_random_graph(n, n * (n - 1) / 4).unwrap();
+            let mps = SchmidMps {}.maximum_planar_subgraph(&graph);
+            let drawing = StraightLineDrawing::new(&mps, layout).unwrap();
+            assert_eq!(drawing.positions.len(), n);
+            assert!(drawing.is_plane(&mps));
+        }
+
+        assert!(StraightLineDrawing::new(&Graph::complete(5), layout).is_none());
+    }
+
+    let mut graph = Graph::empty(6);
+    for (u, v) in [(0, 1), (1, 2), (3, 4)] {
+        graph.add_edge(u, v);
+    }
+    assert!(StraightLineDrawing::tutte(&graph).unwrap().is_plane(&graph));
+}
+
+#[test]
+fn removed_edges_svg_test() {
+    let graph = Graph::complete(6);
+    let result = SchmidMps {}.solve(&graph);
+    let drawing = StraightLineDrawing::new(&result.mps, Layout::Schnyder).unwrap();
+    let svg = drawing.to_svg(&result.mps, &result.removed_edges);
+    assert_eq!(svg.matches("<line").count(), result.mps.num_of_edges());
+    assert_eq!(svg.matches("<path").count(), result.removed_edges.len());
+    assert!(svg.contains("stroke=\"red\""));
+}
+
+#[test]
+fn tikz_test() {
+    let graph = Graph::complete(6);
+    let result = SchmidMps {}.solve(&graph);
+    let drawing = StraightLineDrawing::new(&result.mps, Layout::Schnyder).unwrap();
+    let tikz = drawing.to_tikz(&result.mps, &result.removed_edges, &TikzOptions::default());
+    assert!(tikz.starts_with("\\begin{tikzpicture}[scale=0.5]"));
+    assert!(tikz.ends_with("\\end{tikzpicture}\n"));
+    assert!(tikz.contains("\\node[vertex] (v5)"));
+    assert!(tikz.contains("{5};"));
+    assert_eq!(
+        tikz.matches("\\draw[kept]").count(),
+        result.mps.num_of_edges()
+    );
+    assert_eq!(
+        tikz.matches("\\draw[removed]").count(),
+        result.removed_edges.len()
+    );
+
+    let triangle = Graph::complete(3);
+    let drawing = StraightLineDrawing::from_grid(&[(0, 0), (2, 0), (1, 1)]);
+    let options = TikzOptions {
+        labels: VertexLabels::Custom(vec!["$a$".to_string(), "$b$".to_string()]),
+        ..TikzOptions::default()
+    };
+    let tikz = drawing.to_tikz(&triangle, &[], &options);
+    assert!(tikz.contains("\\node[vertex] (v0) at (0, 0) {$a$};"));
+    assert!(tikz.contains("\\node[vertex] (v1) at (2, 0) {$b$};"));
+    assert!(tikz.contains("\\node[vertex] (v2) at (1, 1) {};"));
+    assert!(tikz.contains("\\draw[kept] (v0) -- (v1);"));
+
+    let options = TikzOptions {
+        labels: VertexLabels::Empty,
+        ..TikzOptions::default()
+    };
+    let filename = std::env::temp_dir().join("tikz_test.tex");
+    let filename = filename.to_str().unwrap();
+    drawing
+        .write_tikz(&triangle, &[], &options, filename)
+        .unwrap();
+    assert_eq!(
+        std::fs::read_to_string(filename).unwrap(),
+        drawing.to_tikz(&triangle, &[], &options)
+    );
+}
@@ -0,0 +1,96 @@
+use crate::edge_list::Indexing;
+use crate::graphs::{Graph, GraphError};
+use crate::match_merge::CalinescuMps;
+use crate::mps_alg::MpsAlgorithm;
+
+#[test]
+fn edge_list_test() {
+    let text =
+        "# a triangle with a pendant vertex\n1 2\n2 3 0.5\n\n% repeated edge\n3 1\n1 3\n3 4\n";
+    let graph = Graph::from_edge_list(text, Indexing::OneBased).unwrap();
+    assert_eq!(graph.num_of_vertices(), 4);
+    assert_eq!(graph.num_of_edges(), 4);
+    assert!(graph.has_edge(0, 2));
+    assert!(graph.has_edge(2, 3));
+
+    let zero_based = Graph::from_edge_list(text, Indexing::ZeroBased).unwrap();
+    assert_eq!(zero_based.num_of_vertices(), 5);
+    assert!(zero_based.has_edge(1, 2));
+
+    for indexing in [Indexing::ZeroBased, Indexing::OneBased] {
+        let k6 = Graph::complete(6);
+        let read = Graph::from_edge_list(&k6.to_edge_list(indexing), indexing).unwrap();
+        assert_eq!(k6, read);
+    }
+
+    assert_eq!(
+        Graph::from_edge_list("1 2\n2\n", Indexing::OneBased),
+        Err(GraphError::Parse {
+            line: 2,
+            message: "expected two vertex indices".to_string()
+        })
+    );
+    assert!(matches!(
+        Graph::from_edge_list("0 1\n", Indexing::OneBased),
+        Err(GraphError::Parse { line: 1, .. })
+    ));
+    assert!(matches!(
+        Graph::from_edge_list("1 2\n3 3\n", Indexing::OneBased),
+        Err(GraphError::Parse { line: 2, .. })
+    ));
+    assert!(matches!(
+        Graph::from_edge_list("0 1\n0 18446744073709551615\n", Indexing::ZeroBased),
+        Err(GraphError::Parse { line: 2, .. })
+    ));
+    assert!(matches!(
+        Graph::from_edge_list("0 1000000000000\n", Indexing::ZeroBased),
+        Err(GraphError::Parse { line: 1, .. })
+    ));
+}
+
+#[test]
+fn dimacs_test() {
+    let text = "c K4 minus an edge\np edge 5 5\ne 1 2\ne 1 3\ne 2 3\ne 2 4\ne 3 4\n";
+    let graph = Graph::from_dimacs(text).unwrap();
+    assert_eq!(graph.num_of_vertices(), 5);
+    assert_eq!(graph.num_of_edges(), 5);
+    assert!(!graph.has_edge(0, 3));
+    assert_eq!(Graph::from_dimacs(&graph.to_dimacs()).unwrap(), graph);
+
+    let result = CalinescuMps {}.solve(&graph);
+    assert!(result.mps.is_planar());
+
+    assert!(matches!(
+        Graph::from_dimacs("e 1 2\np edge 2 1\n"),
+        Err(GraphError::Parse { line: 1, .. })
+    ));
+    assert!(matches!(
+        Graph::from_dimacs("p edge 2 1\ne 1 3\n"),
+        Err(GraphError::Parse { line: 2, .. })
+    ));
+    assert!(matches!(
+        Graph::from_dimacs("p edge 1000000000000 1\n"),
+        Err(GraphError::Parse { line: 1, .. })
+    ));
+    assert!(matches!(
+        Graph::from_dimacs("c only a comment\n"),
+        Err(GraphError::Parse { .. })
+    ));
+}
+
+#[test]
+fn edge_list_file_test() {
+    let filename = std::env::temp_dir().join("edge_list_test.txt");
+    let filename = filename.to_str().unwrap();
+    let graph = Graph::complete(5);
+    graph.write_edge_list(filename, Indexing::OneBased).unwrap();
+    assert_eq!(
+        Graph::read_edge_list(filename, Indexing::OneBased).unwrap(),
+        graph
+    );
+
+    let filename = std::env::temp_dir().join("dimacs_test.col");
+    let filename = filename.to_str().unwrap();
+    graph.write_dimacs(filename).unwrap();
+    assert_eq!(Graph::read_dimacs(filename).unwrap(), graph);
+}
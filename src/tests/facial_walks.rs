@@ -1,5 +1,10 @@
-use crate::facial_walks::facial_walks_mps;
+use crate::facial_walks::{
+    facial_walks_embedding_with_options, facial_walks_mps, facial_walks_mps_with_initial_solution,
+};
 use crate::graphs::Graph;
+use crate::ilp::IlpOptions;
+use crate::match_merge::MyMps;
+use crate::mps_alg::MpsAlgorithm;
 
 #[test]
 fn facial_walks_mps_test() {
@@ -10,3 +15,64 @@ fn facial_walks_mps_test() {
         assert_eq!(mps.num_of_edges(), 3 * n - 6);
     }
 }
+
+#[test]
+fn facial_walks_mps_initial_solution_test() {
+    for n in 4..6 {
+        let graph = Graph::complete(n);
+        let initial = MyMps {}.maximum_planar_subgraph(&graph);
+        let solution = facial_walks_mps_with_initial_solution(&graph, &initial, None);
+        assert!(solution.is_optimal());
+        assert_eq!(solution.mps.num_of_edges(), 3 * n - 6);
+    }
+}
+
+#[test]
+fn facial_walks_embedding_test() {
+    let mut wheel = Graph::complete(4);
+    wheel.add_vertex();
+    for v in [0, 1, 2] {
+        wheel.add_edge(v, 4);
+    }
+
+    for graph in [Graph::complete(4), Graph::complete(5), wheel] {
+        let embedding =
+            facial_walks_embedding_with_options(&graph, &IlpOptions::default()).unwrap();
+        let mps = &embedding.solution.mps;
+        let n = mps.num_of_vertices();
+        let m = mps.num_of_edges();
+        assert_eq!(embedding.faces.len() + n, m + 2);
+
+        let mut arcs = embedding.faces.concat();
+        let mut mps_arcs = mps.all_arcs();
+        arcs.sort_unstable();
+        mps_arcs.sort_unstable();
+        assert_eq!(arcs, mps_arcs);
+        for face in embedding.faces.iter() {
+            for j in 0..face.len() {
+                assert_eq!(face[j].1, face[(j + 1) % face.len()].0);
+            }
+        }
+
+        for v in 0..n {
+            let mut rotation = embedding.rotation[v].clone();
+            rotation.sort_unstable();
+            let mut neighbors = mps
+                .neighbors(v)
+                .unwrap()
+                .iter()
+                .copied()
+                .collect::<Vec<_>>();
+            neighbors.sort_unstable();
+            assert_eq!(rotation, neighbors);
+        }
+
+        let traced = embedding.embedding();
+        assert_eq!(traced.num_of_faces(), embedding.faces.len());
+        for face in traced.faces() {
+            let first = (face[0], face[1 % face.len()]);
+            let matching = embedding.faces.iter().find(|f| f.contains(&first)).unwrap();
+            assert_eq!(matching.len(), face.len());
+        }
+    }
+}
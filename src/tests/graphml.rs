@@ -0,0 +1,110 @@
+use crate::graphs::{Graph, GraphError};
+use crate::match_merge::SchmidMps;
+use crate::mps_alg::MpsAlgorithm;
+
+#[test]
+fn graphml_test() {
+    let graph = Graph::complete(6);
+    let mps = SchmidMps {}.maximum_planar_subgraph(&graph);
+
+    let (read, overlay) = Graph::from_graphml_with_overlay(&graph.to_graphml(Some(&mps))).unwrap();
+    assert_eq!(read, graph);
+    assert_eq!(overlay, Some(mps));
+
+    let (read, overlay) = Graph::from_graphml_with_overlay(&graph.to_graphml(None)).unwrap();
+    assert_eq!(read, graph);
+    assert_eq!(overlay, None);
+
+    let edited = r#"<?xml version="1.0"?>
+<graphml>
+  <!-- exported from an editor -->
+  <key id="d3" for="edge" attr.name="planar" attr.type="boolean"/>
+  <graph edgedefault="directed">
+    <node id='a'/><node id="b"/>
+    <node id="c"></node>
+    <edge source="b" target="a"><data key="d3">false</data></edge>
+    <edge source="b" target="c">
+      <data key="d3">true</data>
+    </edge>
+  </graph>
+</graphml>
+"#;
+    let (read, overlay) = Graph::from_graphml_with_overlay(edited).unwrap();
+    assert_eq!(read.num_of_vertices(), 3);
+    assert!(read.has_edge(0, 1) && read.has_edge(1, 2));
+    let overlay = overlay.unwrap();
+    assert!(!overlay.has_edge(0, 1) && overlay.has_edge(1, 2));
+
+    let defaults = r#"<graphml>
+  <key id="p" for="node" attr.name="planar" attr.type="boolean"/>
+  <key id="q" for="all" attr.name="planar" attr.type="boolean">
+    <default>true</default>
+  </key>
+  <graph edgedefault="undirected">
+    <node id="a"><data key="p">false</data></node>
+    <node id="b>1"/>
+    <node id="c"/>
+    <edge source="a" target="b>1"/>
+    <edge source="b>1" target="c"><data key="q">false</data></edge>
+    <edge source="a" target="c"><data key="p">false</data></edge>
+  </graph>
+</graphml>
+"#;
+    let (read, overlay) = Graph::from_graphml_with_overlay(defaults).unwrap();
+    assert_eq!(read.num_of_vertices(), 3);
+    assert_eq!(read.num_of_edges(), 3);
+    let overlay = overlay.unwrap();
+    assert!(overlay.has_edge(0, 1) && !overlay.has_edge(1, 2) && overlay.has_edge(0, 2));
+
+    assert!(matches!(
+        Graph::from_graphml(
+            "<graphml>\n<node id=\"a\"/>\n<edge source=\"a\" target=\"b\"/>\n</graphml>"
+        ),
+        Err(GraphError::Parse { line: 3, .. })
+    ));
+}
+
+#[test]
+fn gml_test() {
+    let graph = Graph::complete(6);
+    let mps = SchmidMps {}.maximum_planar_subgraph(&graph);
+
+    let (read, overlay) = Graph::from_gml_with_overlay(&graph.to_gml(Some(&mps))).unwrap();
+    assert_eq!(read, graph);
+    assert_eq!(overlay, Some(mps));
+    assert_eq!(Graph::from_gml(&graph.to_gml(None)).unwrap(), graph);
+
+    let edited = "# exported from an editor\nCreator \"someone\"\ngraph [\n  node [ id 10 label \"x [1]\" ]\n  node [ id 20 ]\n  edge [ source 20 target 10 planar 1 ]\n]\n";
+    let (read, overlay) = Graph::from_gml_with_overlay(edited).unwrap();
+    assert_eq!(read.num_of_vertices(), 2);
+    assert!(read.has_edge(0, 1));
+    assert!(overlay.unwrap().has_edge(0, 1));
+
+    assert!(matches!(
+        Graph::from_gml("graph [\n  node [ id 0 ]\n  edge [ source 0 target 0 ]\n]\n"),
+        Err(GraphError::Parse { line: 3, .. })
+    ));
+    assert!(matches!(
+        Graph::from_gml("graph [\n  node [ id 0 ]\n"),
+        Err(GraphError::Parse { .. })
+    ));
+}
+
+#[test]
+fn graphml_file_test() {
+    let graph = Graph::complete(5);
+    let mps = SchmidMps {}.maximum_planar_subgraph(&graph);
+
+    let filename = std::env::temp_dir().join("graphml_test.graphml");
+    let filename = filename.to_str().unwrap();
+    graph.write_graphml(filename, Some(&mps)).unwrap();
+    assert_eq!(
+        Graph::read_graphml(filename).unwrap(),
+        (graph.clone(), Some(mps.clone()))
+    );
+
+    let filename = std::env::temp_dir().join("gml_test.gml");
+    let filename = filename.to_str().unwrap();
+    graph.write_gml(filename, Some(&mps)).unwrap();
+    assert_eq!(Graph::read_gml(filename).unwrap(), (graph, Some(mps)));
+}
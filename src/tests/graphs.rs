@@ -1,4 +1,4 @@
-use crate::graphs::Graph;
+use crate::graphs::{Graph, GraphError};
 
 #[test]
 fn adding_edge() {
@@ -23,10 +23,140 @@ fn create_bipartite_complete() {
 
 #[test]
 fn write_and_read() {
-    let name = "k10_test.json";
+    let path = std::env::temp_dir().join(format!("k10_test_{}.json", std::process::id()));
+    let name = path.to_str().unwrap();
     let k10 = Graph::complete(10);
-    let write = k10.write_to_json(name);
-    assert!(write.is_ok());
-    let read = Graph::read_from_json(name);
+    assert_eq!(k10.write_to_json(name), Ok(()));
+    let read = Graph::read_from_json(name).unwrap();
+    std::fs::remove_file(&path).unwrap();
     assert_eq!(k10, read);
+
+    assert!(matches!(
+        k10.write_to_json(std::env::temp_dir().to_str().unwrap()),
+        Err(GraphError::Io(_))
+    ));
+}
+
+#[test]
+fn fallible_edge_operations() {
+    let mut graph = Graph::empty(3);
+    assert_eq!(graph.try_add_edge(0, 1), Ok(()));
+    assert_eq!(
+        graph.try_add_edge(1, 0),
+        Err(GraphError::DuplicateEdge(1, 0))
+    );
+    assert_eq!(graph.try_add_edge(2, 2), Err(GraphError::SelfLoop(2)));
+    assert_eq!(graph.try_add_edge(0, 3), Err(GraphError::InvalidVertex(3)));
+    assert_eq!(graph.num_of_edges(), 1);
+
+    assert_eq!(graph.try_has_edge(1, 0), Ok(true));
+    assert_eq!(graph.try_has_edge(1, 2), Ok(false));
+    assert_eq!(graph.try_has_edge(5, 0), Err(GraphError::InvalidVertex(5)));
+    assert!(!graph.has_edge(5, 0));
+
+    assert_eq!(
+        graph.try_remove_edge(1, 2),
+        Err(GraphError::MissingEdge(1, 2))
+    );
+    assert_eq!(
+        graph.try_remove_edge(4, 2),
+        Err(GraphError::InvalidVertex(4))
+    );
+    assert_eq!(graph.try_remove_edge(1, 0), Ok(()));
+    assert_eq!(graph.num_of_edges(), 0);
+    assert!(!graph.remove_edge(1, 0));
+}
+
+#[test]
+fn display_edges() {
+    let mut graph = Graph::empty(3);
+    graph.add_edge(2, 1);
+    assert_eq!(graph.to_string(), "Edges:\n1 <-> 2\n");
+}
+
+fn read_json(name: &str, contents: &str) -> Result<Graph, GraphError> {
+    let filename = std::env::temp_dir().join(name);
+    std::fs::write(&filename, contents).unwrap();
+    Graph::read_from_json(filename.to_str().unwrap())
+}
+
+#[test]
+fn read_invalid_json() {
+    let read = read_json(
+        "valid_test.json",
+        r#"{"num_of_vertices":3,"num_of_edges":2,"neighbors":[[1],[0,2],[1]]}"#,
+    );
+    assert_eq!(read.unwrap().num_of_edges(), 2);
+
+    let read = read_json(
+        "asymmetric_test.json",
+        r#"{"num_of_vertices":3,"num_of_edges":1,"neighbors":[[1],[0,2],[]]}"#,
+    );
+    assert_eq!(
+        read,
+        Err(GraphError::AsymmetricNeighbor {
+            vertex: 1,
+            neighbor: 2
+        })
+    );
+
+    let read = read_json(
+        "range_test.json",
+        r#"{"num_of_vertices":2,"num_of_edges":1,"neighbors":[[1],[0,5]]}"#,
+    );
+    assert_eq!(
+        read,
+        Err(GraphError::NeighborOutOfRange {
+            vertex: 1,
+            neighbor: 5
+        })
+    );
+
+    let read = read_json(
+        "loop_test.json",
+        r#"{"num_of_vertices":2,"num_of_edges":1,"neighbors":[[0,1],[0]]}"#,
+    );
+    assert_eq!(read, Err(GraphError::SelfLoop(0)));
+
+    let read = read_json(
+        "duplicate_test.json",
+        r#"{"num_of_vertices":2,"num_of_edges":1,"neighbors":[[1,1],[0]]}"#,
+    );
+    assert_eq!(
+        read,
+        Err(GraphError::DuplicateNeighbor {
+            vertex: 0,
+            neighbor: 1
+        })
+    );
+
+    let read = read_json(
+        "edge_count_test.json",
+        r#"{"num_of_vertices":2,"num_of_edges":3,"neighbors":[[1],[0]]}"#,
+    );
+    assert_eq!(
+        read,
+        Err(GraphError::EdgeCountMismatch {
+            declared: 3,
+            found: 1
+        })
+    );
+
+    let read = read_json(
+        "vertex_count_test.json",
+        r#"{"num_of_vertices":3,"num_of_edges":1,"neighbors":[[1],[0]]}"#,
+    );
+    assert_eq!(
+        read,
+        Err(GraphError::VertexCountMismatch {
+            declared: 3,
+            found: 2
+        })
+    );
+
+    let read = read_json("format_test.json", r#"{"num_of_vertices":2}"#);
+    assert!(matches!(read, Err(GraphError::Json(_))));
+
+    let read = Graph::read_from_json("missing_test.json");
+    assert!(matches!(read, Err(GraphError::Io(_))));
 }
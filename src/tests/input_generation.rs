@@ -1,3 +1,4 @@
+use crate::branch_and_cut::*;
 use crate::facial_walks::*;
 use crate::graphs::Graph;
 use crate::match_merge::*;
@@ -6,7 +7,6 @@ use crate::rand_graphs::*;
 use crate::schnyder::*;
 use std::fs::File;
 use std::io::Write;
-use std::time::Instant;
 
 // #[test]
 fn generate_3regular() {
@@ -45,21 +45,19 @@ fn test_named_approx_algorithms(name: &str) {
     for n in (100..=10000).step_by(100) {
         for k in 0..=9 {
             let filename = format!("{}_n{}_test_{}.json", name, n, k);
-            let graph = Graph::read_from_json(&filename);
+            let graph = Graph::read_from_json(&filename).unwrap();
 
             for alg in algorithms.iter() {
-                let start = Instant::now();
-                let result = alg.maximum_planar_subgraph(&graph);
-                let duration = start.elapsed();
-                assert!(result.is_planar());
+                let result = alg.solve(&graph);
+                assert!(result.mps.is_planar());
                 writeln!(
                     output_file,
                     "{},{},{},{},{},{}",
                     filename,
                     n,
                     k,
-                    duration.as_nanos(),
-                    result.num_of_edges(),
+                    result.wall_time.as_nanos(),
+                    result.mps.num_of_edges(),
                     alg.name()
                 )
                 .unwrap();
@@ -82,18 +80,16 @@ fn test_named_approx_complete() {
         let graph = Graph::complete(n);
         for k in 0..5 {
             for alg in algorithms.iter() {
-                let start = Instant::now();
-                let result = alg.maximum_planar_subgraph(&graph);
-                let duration = start.elapsed();
-                assert!(result.is_planar());
+                let result = alg.solve(&graph);
+                assert!(result.mps.is_planar());
                 writeln!(
                     output_file,
                     "{},{},{},{},{},{}",
                     format!("complete_n{}_test_{}", n, k),
                     n,
                     k,
-                    duration.as_nanos(),
-                    result.num_of_edges(),
+                    result.wall_time.as_nanos(),
+                    result.mps.num_of_edges(),
                     alg.name()
                 )
                 .unwrap();
@@ -105,25 +101,26 @@ fn test_named_approx_complete() {
 // #[test]
 fn test_named_exact_complete() {
     let mut output_file = File::create(format!("results/exact_complete_output.txt")).unwrap();
-    let algorithms: Vec<Box<dyn MpsAlgorithm>> =
-        vec![Box::new(SchnyderMps {}), Box::new(FacialWalksMps {})];
+    let algorithms: Vec<Box<dyn MpsAlgorithm>> = vec![
+        Box::new(SchnyderMps::default()),
+        Box::new(FacialWalksMps::default()),
+        Box::new(BranchAndCutMps::default()),
+    ];
 
     for n in 3..=14 {
         let graph = Graph::complete(n);
         for k in 0..3 {
             for alg in algorithms.iter() {
-                let start = Instant::now();
-                let result = alg.maximum_planar_subgraph(&graph);
-                let duration = start.elapsed();
-                assert!(result.is_planar());
+                let result = alg.solve(&graph);
+                assert!(result.mps.is_planar());
                 writeln!(
                     output_file,
                     "{},{},{},{},{},{}",
                     format!("complete_n{}_test_{}", n, k),
                     n,
                     k,
-                    duration.as_nanos(),
-                    result.num_of_edges(),
+                    result.wall_time.as_nanos(),
+                    result.mps.num_of_edges(),
                     alg.name()
                 )
                 .unwrap();
@@ -140,8 +137,9 @@ fn test_named_mixed_regular() {
         Box::new(SchmidMps {}),
         Box::new(MyMps {}),
         Box::new(PoranenMps {}),
-        Box::new(SchnyderMps {}),
-        Box::new(FacialWalksMps {}),
+        Box::new(SchnyderMps::default()),
+        Box::new(FacialWalksMps::default()),
+        Box::new(BranchAndCutMps::default()),
     ];
 
     for n in (4..=20).step_by(2) {
@@ -149,18 +147,16 @@ fn test_named_mixed_regular() {
         for k in 0..3 {
             let graph = bliztstein_generation(&d_in).unwrap();
             for alg in algorithms.iter() {
-                let start = Instant::now();
-                let result = alg.maximum_planar_subgraph(&graph);
-                let duration = start.elapsed();
-                assert!(result.is_planar());
+                let result = alg.solve(&graph);
+                assert!(result.mps.is_planar());
                 writeln!(
                     output_file,
                     "{},{},{},{},{},{}",
                     format!("regular_n{}_test_{}", n, k),
                     n,
                     k,
-                    duration.as_nanos(),
-                    result.num_of_edges(),
+                    result.wall_time.as_nanos(),
+                    result.mps.num_of_edges(),
                     alg.name()
                 )
                 .unwrap();
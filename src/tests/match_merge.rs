@@ -1,6 +1,9 @@
+use crate::constraints::EdgeConstraints;
 use crate::graphs::Graph;
 use crate::match_merge::*;
-use crate::mps_alg::MpsAlgorithm;
+use crate::mps_alg::{MpsAlgorithm, MpsResult, MpsStatus};
+use crate::rand_graphs::{general_random_graph, random_pareto_graph};
+use crate::weights::EdgeWeights;
 
 #[test]
 fn calinescu_basic_mps_complete_test() {
@@ -58,3 +61,603 @@ fn poranen_mps_complete_test() {
         assert_eq!(mps.num_of_edges(), m);
     }
 }
+
+#[test]
+fn calinescu_result_test() {
+    for n in 3..10 {
+        let graph = Graph::complete(n);
+        let result = CalinescuMps {}.solve(&graph);
+
+        assert_eq!(result.status, MpsStatus::Heuristic);
+        assert_eq!(result.upper_bound, (3 * n - 6) as f64);
+        assert_eq!(
+            result.mps.num_of_edges() + result.removed_edges.len(),
+            graph.num_of_edges()
+        );
+        assert!(result
+            .removed_edges
+            .iter()
+            .all(|&(u, v)| graph.has_edge(u, v) && !result.mps.has_edge(u, v)));
+        assert_eq!(result.counters.get("K3").copied(), Some((n - 1) / 2));
+        assert_eq!(result.counters.get("K2").copied().unwrap_or(0), (n - 1) % 2);
+    }
+}
+
+#[test]
+fn calinescu_weighted_test() {
+    let graph = Graph::complete(6);
+    let mut weights = EdgeWeights::new();
+    for (u, v) in [(3, 4), (4, 5), (3, 5)] {
+        weights.set_weight(u, v, 5.0);
+    }
+    weights.set_weight(0, 3, 2.0);
+
+    let result = CalinescuMps {}.solve_weighted(&graph, &weights);
+    for (u, v) in [(3, 4), (4, 5), (3, 5), (0, 3)] {
+        assert!(result.mps.has_edge(u, v));
+    }
+    assert_eq!(result.weight, 20.0);
+    assert_eq!(result.counters.get("K3").copied(), Some(2));
+    assert_eq!(result.counters.get("K2").copied(), Some(1));
+}
+
+#[test]
+fn match_merge_pareto_test() {
+    let graph = random_pareto_graph(3000, 2.0).unwrap();
+    let algorithms: Vec<Box<dyn MpsAlgorithm>> = vec![
+        Box::new(CalinescuMps {}),
+        Box::new(SchmidMps {}),
+        Box::new(MyMps {}),
+        Box::new(PoranenMps {}),
+        Box::new(FanMps { max_length: 8 }),
+    ];
+
+    for alg in algorithms {
+        let result = alg.solve(&graph);
+        assert!(result.mps.is_planar());
+        assert!(result
+            .mps
+            .all_edges()
+            .into_iter()
+            .all(|(u, v)| graph.has_edge(u, v)));
+        assert!((0..graph.num_of_vertices()).all(|v| {
+            graph.neighbors(v).unwrap().is_empty() || !result.mps.neighbors(v).unwrap().is_empty()
+        }));
+    }
+}
+
+#[test]
+fn match_merge_builder_test() {
+    let graph = general_random_graph(30, 150).unwrap();
+    let presets: Vec<(Box<dyn MpsAlgorithm>, MatchMergeMps)> = vec![
+        (
+            Box::new(CalinescuMps {}),
+            MatchMergeBuilder::new("Calinescu")
+                .then(MatchMergeRule::K3)
+                .capture_components()
+                .then(MatchMergeRule::K2)
+                .build(),
+        ),
+        (
+            Box::new(PoranenMps {}),
+            MatchMergeBuilder::new("Poranen")
+                .repeat(&[
+                    RuleStep::exhaustive(MatchMergeRule::Poranen),
+                    RuleStep::once(MatchMergeRule::K3),
+                ])
+                .capture_components()
+                .then(MatchMergeRule::K2)
+                .build(),
+        ),
+    ];
+
+    for (preset, built) in presets {
+        let expected = preset.solve(&graph);
+        let result = built.solve(&graph);
+        let mut expected_edges = expected.mps.all_edges();
+        let mut edges = result.mps.all_edges();
+        expected_edges.sort_unstable();
+        edges.sort_unstable();
+
+        assert_eq!(built.name(), preset.name());
+        assert_eq!(edges, expected_edges);
+        assert_eq!(result.counters, expected.counters);
+        assert_eq!(result.components, expected.components);
+    }
+
+    let triangles = MatchMergeBuilder::new("K3")
+        .then(MatchMergeRule::K3)
+        .build();
+    assert_eq!(triangles.phases().len(), 1);
+    assert_eq!(triangles.capture(), None);
+    assert_eq!(CalinescuMps {}.pipeline().capture(), Some(1));
+    let result = triangles.solve(&Graph::complete(6));
+    assert_eq!(result.mps.num_of_edges(), 6);
+    assert_eq!(result.counters.get("K3").copied(), Some(2));
+    assert_eq!(result.components.unwrap().iter().max().copied(), Some(1));
+}
+
+/// A rule merging the components of the ends of edges whose indices have the same parity.
+struct SameParityRule;
+
+impl Rule for SameParityRule {
+    fn name(&self) -> &'static str {
+        "SameParity"
+    }
+
+    fn find(&self, state: &MatchMerge, x: usize, search: &mut RuleSearch) {
+        for &y in state.full().neighbors(x).unwrap() {
+            if (x + y).is_multiple_of(2)
+                && state.component(x) != state.component(y)
+                && search.offer(&[(x, y)], &[x, y])
+            {
+                return;
+            }
+        }
+    }
+
+    fn apply(&self, _state: &MatchMerge, structure: &[usize]) -> RuleApplication {
+        RuleApplication {
+            edges: vec![(structure[0], structure[1])],
+            vertices: structure.to_vec(),
+        }
+    }
+}
+
+#[test]
+fn custom_rule_test() {
+    let graph = Graph::complete(6);
+    let result = MatchMergeBuilder::new("Parity")
+        .then(SameParityRule)
+        .capture_components()
+        .then(MatchMergeRule::K2)
+        .build()
+        .solve(&graph);
+    assert_eq!(result.mps.num_of_edges(), 5);
+    assert_eq!(result.counters.get("SameParity").copied(), Some(4));
+    assert_eq!(result.counters.get("K2").copied(), Some(1));
+    assert_eq!(result.components, Some(vec![0, 1, 0, 1, 0, 1]));
+
+    let graph = general_random_graph(30, 200).unwrap();
+    let expected = MyMps {}.solve(&graph);
+    let result = MatchMergeBuilder::new("Fans")
+        .then(FanRule::new(4, "My"))
+        .then(FanRule::new(3, "D4"))
+        .then(MatchMergeRule::K3)
+        .then(FanRule::new(1, "K2"))
+        .build()
+        .solve(&graph);
+    let mut expected_edges = expected.mps.all_edges();
+    let mut edges = result.mps.all_edges();
+    expected_edges.sort_unstable();
+    edges.sort_unstable();
+    assert_eq!(edges, expected_edges);
+    assert_eq!(result.counters, expected.counters);
+
+    let fan = FanRule::new(5, "Fan5");
+    assert_eq!(fan.length(), 5);
+    let result = MatchMergeBuilder::new("Fan5")
+        .then(fan)
+        .build()
+        .solve(&Graph::complete(6));
+    assert_eq!(result.mps.num_of_edges(), 9);
+    assert_eq!(result.counters.get("Fan5").copied(), Some(1));
+}
+
+#[test]
+fn fan_mps_complete_test() {
+    for n in 3..10 {
+        let graph = Graph::complete(n);
+        let result = FanMps { max_length: n }.solve(&graph);
+        assert_eq!(result.mps.num_of_edges(), 2 * n - 3);
+        assert_eq!(result.counters.get("Fan").copied(), Some(1));
+        assert!(
+            result.mps.num_of_edges() >= MyMps {}.maximum_planar_subgraph(&graph).num_of_edges()
+        );
+    }
+
+    for (max_length, m) in [(2, 12), (3, 13), (4, 14), (8, 15)] {
+        let result = FanMps { max_length }.solve(&Graph::complete(9));
+        assert_eq!(result.mps.num_of_edges(), m);
+        assert!(result.mps.is_planar());
+    }
+}
+
+#[test]
+fn longest_fan_rule_test() {
+    let mut graph = Graph::empty(7);
+    for v in 1..7 {
+        graph.add_edge(0, v);
+    }
+    for v in 1..6 {
+        graph.add_edge(v, v + 1);
+    }
+
+    let rule = LongestFanRule::new(10);
+    assert_eq!(rule.max_length(), 10);
+    let result = MatchMergeBuilder::new("Fan")
+        .then(rule)
+        .build()
+        .solve(&graph);
+    assert_eq!(result.counters.get("Fan").copied(), Some(1));
+    assert_eq!(result.mps.num_of_edges(), graph.num_of_edges());
+
+    let result = MatchMergeBuilder::new("Fan")
+        .then(LongestFanRule::new(3))
+        .then(MatchMergeRule::K2)
+        .build()
+        .solve(&graph);
+    assert_eq!(result.counters.get("Fan").copied(), Some(2));
+    assert!(result.mps.is_planar());
+    assert!((0..7).all(|v| !result.mps.neighbors(v).unwrap().is_empty()));
+}
+
+#[test]
+fn seeded_match_merge_test() {
+    let graph = general_random_graph(30, 120).unwrap();
+    let sorted_edges = |result: &MpsResult| {
+        let mut edges = result.mps.all_edges();
+        edges.sort_unstable();
+        edges
+    };
+
+    let pipeline = SchmidMps {}.pipeline();
+    assert_eq!(pipeline.seed(), None);
+    assert_eq!(
+        sorted_edges(&pipeline.solve(&graph)),
+        sorted_edges(&SchmidMps {}.solve(&graph))
+    );
+
+    let seeded = pipeline.with_seed(7);
+    assert_eq!(seeded.seed(), Some(7));
+    let result = seeded.solve(&graph);
+    assert!(result.mps.is_planar());
+    assert_eq!(sorted_edges(&result), sorted_edges(&seeded.solve(&graph)));
+
+    let built = MatchMergeBuilder::new("Schmid")
+        .then(MatchMergeRule::D4)
+        .then(MatchMergeRule::K3)
+        .then(MatchMergeRule::K2)
+        .seed(7)
+        .build();
+    assert_eq!(sorted_edges(&built.solve(&graph)), sorted_edges(&result));
+
+    let subgraphs = (0..20)
+        .map(|seed| sorted_edges(&pipeline.with_seed(seed).solve(&graph)))
+        .collect::<Vec<_>>();
+    assert!(subgraphs.iter().any(|edges| *edges != subgraphs[0]));
+
+    // a copy built edge by edge has adjacency sets iterated in another order
+    let mut copy = Graph::empty(graph.num_of_vertices());
+    for (u, v) in graph.all_edges().into_iter().rev() {
+        copy.add_edge(u, v);
+    }
+    let algorithms = [
+        CalinescuMps {}.pipeline(),
+        SchmidMps {}.pipeline(),
+        MyMps {}.pipeline(),
+        PoranenMps {}.pipeline(),
+        FanMps { max_length: 6 }.pipeline(),
+    ];
+    for alg in algorithms {
+        let seeded = alg.with_seed(3);
+        assert_eq!(
+            sorted_edges(&seeded.solve(&copy)),
+            sorted_edges(&seeded.solve(&graph))
+        );
+        assert_eq!(
+            sorted_edges(&alg.solve(&copy)),
+            sorted_edges(&alg.solve(&graph))
+        );
+    }
+}
+
+#[test]
+fn multi_start_test() {
+    let graph = general_random_graph(30, 120).unwrap();
+    let multi_start = |parallel| MultiStartMps {
+        algorithm: PoranenMps {}.pipeline(),
+        name: "Multi-start Poranen",
+        restarts: 8,
+        seed: 42,
+        parallel,
+    };
+
+    let sequential =
+        multi_start(false).solve_all(&graph, &EdgeWeights::new(), &EdgeConstraints::new());
+    let parallel =
+        multi_start(true).solve_all(&graph, &EdgeWeights::new(), &EdgeConstraints::new());
+    let weights = |results: Vec<MpsResult>| results.iter().map(|r| r.weight).collect::<Vec<f64>>();
+    let sequential = weights(sequential.unwrap());
+    assert_eq!(sequential.len(), 8);
+    assert_eq!(sequential, weights(parallel.unwrap()));
+
+    let result = multi_start(true).solve(&graph);
+    assert_eq!(multi_start(true).name(), "Multi-start Poranen");
+    assert!(result.mps.is_planar());
+    assert_eq!(
+        result.weight,
+        sequential.iter().copied().fold(0.0, f64::max)
+    );
+    assert_eq!(result.counters.get("Restarts").copied(), Some(8));
+}
+
+#[test]
+fn duplicate_rule_names_test() {
+    let graph = general_random_graph(30, 200).unwrap();
+    let expected = MyMps {}.solve(&graph);
+    let result = MatchMergeBuilder::new("X")
+        .then(FanRule::new(4, "X"))
+        .then(FanRule::new(3, "X"))
+        .then(MatchMergeRule::K3)
+        .then(MatchMergeRule::K2)
+        .build()
+        .solve(&graph);
+
+    let mut expected_edges = expected.mps.all_edges();
+    let mut edges = result.mps.all_edges();
+    expected_edges.sort_unstable();
+    edges.sort_unstable();
+    assert_eq!(edges, expected_edges);
+    assert_eq!(
+        result.counters.get("X").copied().unwrap_or(0),
+        expected.counters.get("My").copied().unwrap_or(0)
+            + expected.counters.get("D4").copied().unwrap_or(0)
+    );
+}
+
+/// The Match and Merge algorithms as they were before the rule queues, rescanning every vertex for each application.
+struct ReferenceMatchMerge {
+    full: Graph,
+    mps: Graph,
+    component: Vec<usize>,
+    counters: std::collections::BTreeMap<&'static str, usize>,
+}
+
+impl ReferenceMatchMerge {
+    fn new(g: &Graph) -> Self {
+        let n = g.num_of_vertices();
+        ReferenceMatchMerge {
+            full: g.clone(),
+            mps: Graph::empty(n),
+            component: (0..n).collect(),
+            counters: std::collections::BTreeMap::new(),
+        }
+    }
+
+    fn neighbors(&self, v: usize) -> Vec<usize> {
+        let mut neighbors = self
+            .full
+            .neighbors(v)
+            .unwrap()
+            .iter()
+            .copied()
+            .collect::<Vec<usize>>();
+        neighbors.sort_unstable();
+        neighbors
+    }
+
+    fn different(&self, vertices: &[usize]) -> bool {
+        (0..vertices.len()).all(|i| {
+            (i + 1..vertices.len())
+                .all(|j| self.component[vertices[i]] != self.component[vertices[j]])
+        })
+    }
+
+    fn labels(&self) -> Vec<usize> {
+        let mut labels = vec![usize::MAX; self.component.len()];
+        let mut next = 0;
+        for &c in &self.component {
+            if labels[c] == usize::MAX {
+                labels[c] = next;
+                next += 1;
+            }
+        }
+        self.component.iter().map(|&c| labels[c]).collect()
+    }
+
+    fn apply(&mut self, rule: &'static str, edges: &[(usize, usize)], vertices: &[usize]) {
+        for &(u, v) in edges {
+            self.mps.add_edge(u, v);
+            self.full.remove_edge(u, v);
+        }
+        let target = self.component[vertices[0]];
+        let merged = vertices
+            .iter()
+            .map(|&v| self.component[v])
+            .collect::<Vec<usize>>();
+        for c in self.component.iter_mut() {
+            if merged.contains(c) {
+                *c = target;
+            }
+        }
+        *self.counters.entry(rule).or_insert(0) += 1;
+    }
+
+    fn find_my(&self) -> Option<[usize; 5]> {
+        for x in 0..self.component.len() {
+            let xn = self.neighbors(x);
+            for &y in &xn {
+                for &z in &xn {
+                    if y == z || !self.full.has_edge(y, z) {
+                        continue;
+                    }
+                    for &w in &xn {
+                        if w == y || w == z || !self.full.has_edge(z, w) {
+                            continue;
+                        }
+                        for &t in &xn {
+                            if t == y || t == z || t == w || !self.full.has_edge(w, t) {
+                                continue;
+                            }
+                            if self.different(&[x, y, z, w, t]) {
+                                return Some([x, y, z, w, t]);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    fn find_d4(&self) -> Option<[usize; 4]> {
+        for x in 0..self.component.len() {
+            let xn = self.neighbors(x);
+            for &y in &xn {
+                for &z in &xn {
+                    for &w in &xn {
+                        if y == z || y == w || z == w {
+                            continue;
+                        }
+                        if !self.full.has_edge(y, z) || !self.full.has_edge(z, w) {
+                            continue;
+                        }
+                        if self.different(&[x, y, z, w]) {
+                            return Some([x, y, z, w]);
+                        }
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    fn find_k3(&self) -> Option<[usize; 3]> {
+        for x in 0..self.component.len() {
+            let xn = self.neighbors(x);
+            for &y in &xn {
+                for &z in &xn {
+                    if y != z && self.full.has_edge(y, z) && self.different(&[x, y, z]) {
+                        return Some([x, y, z]);
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    fn find_poranen(&self) -> Option<[usize; 3]> {
+        for x in 0..self.component.len() {
+            let xn = self.neighbors(x);
+            for &y in &xn {
+                for &z in &xn {
+                    if y != z && self.mps.has_edge(y, z) && self.different(&[x, y]) {
+                        return Some([x, y, z]);
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    fn find_k2(&self) -> Option<[usize; 2]> {
+        for x in 0..self.component.len() {
+            for y in self.neighbors(x) {
+                if self.different(&[x, y]) {
+                    return Some([x, y]);
+                }
+            }
+        }
+        None
+    }
+
+    fn exhaust_my(&mut self) {
+        while let Some([x, y, z, w, t]) = self.find_my() {
+            let edges = [(x, y), (x, z), (x, w), (x, t), (y, z), (z, w), (w, t)];
+            self.apply("My", &edges, &[x, y, z, w, t]);
+        }
+    }
+
+    fn exhaust_d4(&mut self) {
+        while let Some([x, y, z, w]) = self.find_d4() {
+            self.apply(
+                "D4",
+                &[(x, y), (x, z), (x, w), (y, z), (w, z)],
+                &[x, y, z, w],
+            );
+        }
+    }
+
+    fn exhaust_k3(&mut self) {
+        while let Some([u, v, w]) = self.find_k3() {
+            self.apply("K3", &[(u, v), (v, w), (w, u)], &[u, v, w]);
+        }
+    }
+
+    fn poranen_phase(&mut self) {
+        let mut found = true;
+        while found {
+            found = false;
+            while let Some([x, y, z]) = self.find_poranen() {
+                self.apply("Poranen", &[(x, y), (x, z)], &[x, y, z]);
+                found = true;
+            }
+            if let Some([u, v, w]) = self.find_k3() {
+                self.apply("K3", &[(u, v), (v, w), (w, u)], &[u, v, w]);
+                found = true;
+            }
+        }
+    }
+
+    fn finish(mut self) -> ReferenceResult {
+        let components = self.labels();
+        while let Some([u, v]) = self.find_k2() {
+            self.apply("K2", &[(u, v)], &[u, v]);
+        }
+        let mut edges = self.mps.all_edges();
+        edges.sort_unstable();
+        (edges, self.counters, components)
+    }
+}
+
+type ReferenceResult = (
+    Vec<(usize, usize)>,
+    std::collections::BTreeMap<&'static str, usize>,
+    Vec<usize>,
+);
+
+fn reference_solve(g: &Graph, name: &str) -> ReferenceResult {
+    let mut mm = ReferenceMatchMerge::new(g);
+    match name {
+        "Calinescu" => mm.exhaust_k3(),
+        "Schmid" => {
+            mm.exhaust_d4();
+            mm.exhaust_k3();
+        }
+        "My" => {
+            mm.exhaust_my();
+            mm.exhaust_d4();
+            mm.exhaust_k3();
+        }
+        _ => mm.poranen_phase(),
+    }
+    mm.finish()
+}
+
+#[test]
+fn match_merge_reference_test() {
+    let mut graphs = (5..25)
+        .map(|n| general_random_graph(n, n * (n - 1) / 4).unwrap())
+        .collect::<Vec<Graph>>();
+    graphs.push(random_pareto_graph(2000, 2.0).unwrap());
+    let algorithms: Vec<Box<dyn MpsAlgorithm>> = vec![
+        Box::new(CalinescuMps {}),
+        Box::new(SchmidMps {}),
+        Box::new(MyMps {}),
+        Box::new(PoranenMps {}),
+    ];
+
+    for graph in &graphs {
+        for alg in &algorithms {
+            let result = alg.solve(graph);
+            let (expected_edges, counters, components) = reference_solve(graph, alg.name());
+
+            let mut edges = result.mps.all_edges();
+            edges.sort_unstable();
+            assert_eq!(edges, expected_edges, "{}", alg.name());
+            assert_eq!(result.counters, counters, "{}", alg.name());
+            assert_eq!(result.components, Some(components), "{}", alg.name());
+        }
+    }
+}
@@ -4,6 +4,9 @@ pub mod graphs;
 #[cfg(test)]
 pub mod rand_graphs;
 
+#[cfg(test)]
+pub mod planarity;
+
 #[cfg(test)]
 pub mod match_merge;
 
@@ -15,3 +18,33 @@ pub mod schnyder;
 
 #[cfg(test)]
 pub mod facial_walks;
+
+#[cfg(test)]
+pub mod branch_and_cut;
+
+#[cfg(test)]
+pub mod schnyder_wood;
+
+#[cfg(test)]
+pub mod weights;
+
+#[cfg(test)]
+pub mod constraints;
+
+#[cfg(test)]
+pub mod post_processing;
+
+#[cfg(test)]
+pub mod edge_list;
+
+#[cfg(test)]
+pub mod nauty;
+
+#[cfg(test)]
+pub mod graphml;
+
+#[cfg(test)]
+pub mod dot;
+
+#[cfg(test)]
+pub mod drawing;
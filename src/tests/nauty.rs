@@ -0,0 +1,127 @@
+use crate::facial_walks::FacialWalksMps;
+use crate::graphs::{Graph, GraphError};
+use crate::match_merge::*;
+use crate::mps_alg::MpsAlgorithm;
+use crate::rand_graphs::general_random_graph;
+
+#[test]
+fn graph6_test() {
+    assert_eq!(Graph::complete(4).to_graph6(), "C~");
+    assert_eq!(Graph::empty(0).to_graph6(), "?");
+
+    let mut path = Graph::empty(5);
+    for v in 1..5 {
+        path.add_edge(v - 1, v);
+    }
+    assert_eq!(path.to_graph6(), "DhC");
+    assert_eq!(Graph::from_graph6(">>graph6<<DhC\n").unwrap(), path);
+
+    let large = Graph::complete(70);
+    assert!(large.to_graph6().starts_with("~?@E"));
+    assert_eq!(Graph::from_graph6(&large.to_graph6()).unwrap(), large);
+
+    assert!(matches!(
+        Graph::from_graph6("C~~"),
+        Err(GraphError::Parse { line: 1, .. })
+    ));
+    assert!(matches!(
+        Graph::from_graph6("C !"),
+        Err(GraphError::Parse { line: 1, .. })
+    ));
+    assert!(matches!(
+        Graph::from_graph6("~~~~~~~~"),
+        Err(GraphError::Parse { line: 1, .. })
+    ));
+}
+
+#[test]
+fn sparse6_test() {
+    let mut graph = Graph::empty(7);
+    for (u, v) in [(0, 1), (0, 2), (1, 2), (5, 6)] {
+        graph.add_edge(u, v);
+    }
+    assert_eq!(graph.to_sparse6(), ":Fa@x^");
+    assert_eq!(Graph::from_sparse6(":Fa@x^").unwrap(), graph);
+    assert_eq!(Graph::from_sparse6(">>sparse6<<:Fa@x^").unwrap(), graph);
+
+    for n in [4, 8, 16, 33, 100] {
+        let graph = general_random_graph(n, n + 2).unwrap();
+        assert_eq!(Graph::from_sparse6(&graph.to_sparse6()).unwrap(), graph);
+    }
+
+    let mut edge = Graph::empty(2);
+    edge.add_edge(0, 1);
+    assert_eq!(edge.to_sparse6(), ":An");
+    assert_eq!(Graph::from_sparse6(":An").unwrap(), edge);
+
+    // vertex n - 2 is the last one with an edge, so the padding starts with a zero bit
+    let mut path = Graph::empty(4);
+    path.add_edge(0, 2);
+    path.add_edge(1, 2);
+    assert_eq!(path.to_sparse6(), ":CoJ");
+    assert_eq!(Graph::from_sparse6(":CoJ").unwrap(), path);
+
+    let mut edge = Graph::empty(4);
+    edge.add_edge(0, 1);
+    assert_eq!(edge.to_sparse6(), ":Cf");
+    assert_eq!(Graph::from_sparse6(":Cf").unwrap(), edge);
+
+    assert!(matches!(
+        Graph::from_sparse6("Fa@x^"),
+        Err(GraphError::Parse { line: 1, .. })
+    ));
+    assert!(matches!(
+        Graph::from_sparse6(":~~~~~~~~"),
+        Err(GraphError::Parse { line: 1, .. })
+    ));
+}
+
+#[test]
+fn nauty_lines_test() {
+    let text = "C~\n\n:Fa@x^\nDhC\n";
+    let graphs = Graph::from_nauty_lines(text).unwrap();
+    assert_eq!(graphs.len(), 3);
+    assert_eq!(graphs[0], Graph::complete(4));
+    assert_eq!(graphs[1].num_of_edges(), 4);
+    assert_eq!(graphs[2].num_of_edges(), 4);
+
+    assert!(matches!(
+        Graph::from_nauty_lines("C~\nC~~\n"),
+        Err(GraphError::Parse { line: 2, .. })
+    ));
+    assert!(matches!(
+        Graph::from_nauty_lines(";Fa@x^\n"),
+        Err(GraphError::Parse { line: 1, .. })
+    ));
+
+    let graphs = (3..8).map(Graph::complete).collect::<Vec<_>>();
+    for sparse in [false, true] {
+        let filename = std::env::temp_dir().join(format!("nauty_test_{sparse}.txt"));
+        let filename = filename.to_str().unwrap();
+        Graph::write_nauty(&graphs, filename, sparse).unwrap();
+        assert_eq!(Graph::read_nauty(filename).unwrap(), graphs);
+    }
+}
+
+#[test]
+fn nauty_exhaustive_test() {
+    // all graphs on five vertices with at least nine edges, as listed by `geng 5 9:10`
+    let graphs = Graph::from_nauty_lines("D~k\nD~{\nD~~\n").unwrap();
+    let exact = FacialWalksMps::default();
+    let heuristics: Vec<Box<dyn MpsAlgorithm>> = vec![
+        Box::new(CalinescuMps {}),
+        Box::new(SchmidMps {}),
+        Box::new(MyMps {}),
+        Box::new(PoranenMps {}),
+    ];
+
+    for graph in graphs {
+        let optimum = exact.solve(&graph).mps.num_of_edges();
+        assert_eq!(optimum, graph.num_of_edges().min(9));
+        for alg in heuristics.iter() {
+            let result = alg.solve(&graph);
+            assert!(result.mps.is_planar());
+            assert!(result.mps.num_of_edges() <= optimum);
+        }
+    }
+}
@@ -0,0 +1,194 @@
+use crate::graphs::Graph;
+use crate::match_merge::PoranenMps;
+use crate::mps_alg::MpsAlgorithm;
+use crate::planarity::{triangulate, IncrementalPlanarSubgraph, PlanarEmbedding};
+use crate::rand_graphs::general_random_graph;
+
+fn assert_valid_embedding(graph: &Graph) {
+    assert_embedding_of(graph, &graph.planar_embedding().unwrap());
+}
+
+fn assert_embedding_of(graph: &Graph, embedding: &PlanarEmbedding) {
+    for v in graph.vertices() {
+        let mut rotation = embedding.rotation(v).unwrap().clone();
+        let mut neighbors = graph
+            .neighbors(v)
+            .unwrap()
+            .iter()
+            .cloned()
+            .collect::<Vec<_>>();
+        rotation.sort();
+        neighbors.sort();
+        assert_eq!(rotation, neighbors);
+    }
+
+    // Euler's formula: n - m + f = 2 for components with edges and 1 for isolated vertices
+    let mut component = vec![None; graph.num_of_vertices()];
+    let mut expected = 0;
+    for v in graph.vertices() {
+        if component[v].is_some() {
+            continue;
+        }
+        component[v] = Some(v);
+        let mut stack = vec![v];
+        while let Some(u) = stack.pop() {
+            for &w in graph.neighbors(u).unwrap() {
+                if component[w].is_none() {
+                    component[w] = Some(v);
+                    stack.push(w);
+                }
+            }
+        }
+        expected += if graph.degree(v) > 0 { 2 } else { 1 };
+    }
+
+    let n = graph.num_of_vertices() as isize;
+    let m = graph.num_of_edges() as isize;
+    let f = embedding.num_of_faces() as isize;
+    assert_eq!(n - m + f, expected);
+
+    let face_lengths = embedding.faces().iter().map(|f| f.len()).sum::<usize>();
+    assert_eq!(face_lengths, 2 * graph.num_of_edges());
+}
+
+#[test]
+fn complete_embedding_test() {
+    for n in 1..=4 {
+        assert_valid_embedding(&Graph::complete(n));
+    }
+
+    let k4 = Graph::complete(4).planar_embedding().unwrap();
+    assert_eq!(k4.num_of_faces(), 4);
+    assert!(k4.faces().iter().all(|face| face.len() == 3));
+}
+
+#[test]
+fn non_planar_embedding_test() {
+    assert!(Graph::complete(5).planar_embedding().is_none());
+    assert!(Graph::bipartite_complete(3, 3).planar_embedding().is_none());
+    assert!(Graph::bipartite_complete(2, 10)
+        .planar_embedding()
+        .is_some());
+}
+
+#[test]
+fn random_embedding_test() {
+    for n in [10, 20, 50] {
+        for m in [n - 1, n + n / 2, 2 * n, 3 * n - 6] {
+            for _ in 0..20 {
+                let graph = general_random_graph(n, m.min(n * (n - 1) / 2)).unwrap();
+                assert_eq!(graph.planar_embedding().is_some(), graph.is_planar());
+                if graph.is_planar() {
+                    assert_valid_embedding(&graph);
+                }
+
+                let mps = PoranenMps {}.maximum_planar_subgraph(&graph);
+                assert_valid_embedding(&mps);
+            }
+        }
+    }
+}
+
+fn assert_kuratowski_subgraph(graph: &Graph) {
+    let edges = graph.kuratowski_subgraph().unwrap();
+    let mut subgraph = Graph::empty(graph.num_of_vertices());
+    for &(u, v) in edges.iter() {
+        assert!(graph.has_edge(u, v));
+        subgraph.add_edge(u, v);
+    }
+    assert!(!subgraph.is_planar());
+
+    // a subdivision of K5 has five branch vertices of degree 4, one of K3,3 six of degree 3
+    let mut branch_degrees = subgraph
+        .vertices()
+        .map(|v| subgraph.degree(v))
+        .filter(|&d| d > 2)
+        .collect::<Vec<_>>();
+    branch_degrees.sort();
+    assert!(branch_degrees == vec![4; 5] || branch_degrees == vec![3; 6]);
+
+    for &(u, v) in edges.iter() {
+        subgraph.remove_edge(u, v);
+        assert!(subgraph.is_planar());
+        subgraph.add_edge(u, v);
+    }
+}
+
+#[test]
+fn kuratowski_subgraph_test() {
+    assert_eq!(Graph::complete(5).kuratowski_subgraph().unwrap().len(), 10);
+    assert_eq!(
+        Graph::bipartite_complete(3, 3)
+            .kuratowski_subgraph()
+            .unwrap()
+            .len(),
+        9
+    );
+    assert!(Graph::complete(4).kuratowski_subgraph().is_none());
+
+    for n in 6..12 {
+        assert_kuratowski_subgraph(&Graph::complete(n));
+    }
+}
+
+#[test]
+fn random_kuratowski_subgraph_test() {
+    for n in [10, 20, 50] {
+        for _ in 0..20 {
+            let graph = general_random_graph(n, 3 * n).unwrap();
+            assert_kuratowski_subgraph(&graph);
+        }
+    }
+}
+
+#[test]
+fn triangulate_test() {
+    let poranen = PoranenMps {};
+
+    for n in [3, 4, 10, 30] {
+        for m in [0, n / 2, n, 2 * n] {
+            let graph = poranen
+                .maximum_planar_subgraph(&general_random_graph(n, m.min(n * (n - 1) / 2)).unwrap());
+            let (triangulation, embedding) = triangulate(&graph).unwrap();
+
+            assert_eq!(triangulation.num_of_edges(), 3 * n - 6);
+            assert!(graph
+                .all_edges()
+                .iter()
+                .all(|&(u, v)| triangulation.has_edge(u, v)));
+            assert!(embedding.faces().iter().all(|face| face.len() == 3));
+            assert_embedding_of(&triangulation, &embedding);
+        }
+    }
+
+    assert!(triangulate(&Graph::complete(5)).is_none());
+}
+
+#[test]
+fn incremental_planar_subgraph_test() {
+    for n in [5, 10, 20, 40] {
+        for _ in 0..10 {
+            let graph = general_random_graph(n, (3 * n).min(n * (n - 1) / 2)).unwrap();
+            let mut subgraph = IncrementalPlanarSubgraph::new(&Graph::empty(n)).unwrap();
+
+            for (u, v) in graph.all_edges() {
+                let mut expected = subgraph.graph().clone();
+                expected.add_edge(u, v);
+                assert_eq!(subgraph.try_add_edge(u, v), expected.is_planar());
+                assert_embedding_of(subgraph.graph(), &subgraph.embedding());
+            }
+
+            let edges = subgraph.graph().all_edges();
+            for &(u, v) in edges.iter().step_by(3) {
+                assert!(subgraph.remove_edge(u, v));
+                assert!(!subgraph.remove_edge(u, v));
+            }
+            assert_embedding_of(subgraph.graph(), &subgraph.embedding());
+
+            for &(u, v) in edges.iter().step_by(3) {
+                assert!(subgraph.try_add_edge(u, v));
+            }
+            assert_embedding_of(subgraph.graph(), &subgraph.embedding());
+        }
+    }
+}
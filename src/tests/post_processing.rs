@@ -0,0 +1,127 @@
+use crate::constraints::EdgeConstraints;
+use crate::graphs::Graph;
+use crate::match_merge::*;
+use crate::mps_alg::MpsAlgorithm;
+use crate::post_processing::{local_search, maximalize, LocalSearchMps, MaximalizedMps};
+use crate::rand_graphs::general_random_graph;
+use crate::weights::EdgeWeights;
+use std::time::Duration;
+
+#[test]
+fn maximalize_test() {
+    for n in [10, 15, 30] {
+        let graph = general_random_graph(n, 4 * n).unwrap();
+        let initial = CalinescuMps {}.maximum_planar_subgraph(&graph);
+        let mps = maximalize(
+            &graph,
+            &initial,
+            &EdgeWeights::new(),
+            &EdgeConstraints::new(),
+        )
+        .unwrap();
+
+        assert!(mps.is_planar());
+        assert!(initial.all_edges().iter().all(|&(u, v)| mps.has_edge(u, v)));
+        for (u, v) in graph.all_edges() {
+            if mps.has_edge(u, v) {
+                continue;
+            }
+            let mut h = mps.clone();
+            h.add_edge(u, v);
+            assert!(!h.is_planar());
+        }
+    }
+}
+
+#[test]
+fn maximalized_mps_test() {
+    for n in 3..10 {
+        let graph = Graph::complete(n);
+        let alg = MaximalizedMps {
+            algorithm: Box::new(CalinescuMps {}),
+            name: "CalinescuMax",
+        };
+        let result = alg.solve(&graph);
+
+        assert_eq!(alg.name(), "CalinescuMax");
+        assert_eq!(result.mps.num_of_edges(), 3 * n - 6);
+        assert_eq!(result.weight, (3 * n - 6) as f64);
+        assert_eq!(
+            result.removed_edges.len(),
+            graph.num_of_edges() - (3 * n - 6)
+        );
+        let added = result.counters["Maximalization"];
+        assert_eq!(
+            added,
+            3 * n
+                - 6
+                - CalinescuMps {}
+                    .maximum_planar_subgraph(&graph)
+                    .num_of_edges()
+        );
+    }
+
+    let graph = Graph::complete(6);
+    let mut constraints = EdgeConstraints::new();
+    constraints.forbid(0, 1);
+    let alg = MaximalizedMps {
+        algorithm: Box::new(SchmidMps {}),
+        name: "SchmidMax",
+    };
+    let result = alg
+        .solve_constrained(&graph, &EdgeWeights::new(), &constraints)
+        .unwrap();
+    assert!(result.mps.is_planar());
+    assert!(!result.mps.has_edge(0, 1));
+}
+
+#[test]
+fn local_search_test() {
+    for n in [10, 20, 30] {
+        let graph = general_random_graph(n, 3 * n).unwrap();
+        let initial = CalinescuMps {}.maximum_planar_subgraph(&graph);
+        let mut constraints = EdgeConstraints::new();
+        let (u, v) = initial.all_edges()[0];
+        constraints.require(u, v);
+
+        let (mps, swaps) =
+            local_search(&graph, &initial, &EdgeWeights::new(), &constraints, None).unwrap();
+
+        assert!(mps.is_planar());
+        assert!(mps.has_edge(u, v));
+        assert!(mps.num_of_edges() >= initial.num_of_edges() + swaps);
+        assert!(mps.all_edges().iter().all(|&(x, y)| graph.has_edge(x, y)));
+        for (x, y) in graph.all_edges() {
+            if !mps.has_edge(x, y) {
+                let mut h = mps.clone();
+                h.add_edge(x, y);
+                assert!(!h.is_planar());
+            }
+        }
+    }
+}
+
+#[test]
+fn local_search_mps_test() {
+    let graph = general_random_graph(20, 60).unwrap();
+    let alg = LocalSearchMps {
+        algorithm: Box::new(PoranenMps {}),
+        name: "PoranenLS",
+        time_limit: Some(Duration::ZERO),
+    };
+    let result = alg.solve(&graph);
+    assert_eq!(result.counters["Swaps"], 0);
+    assert!(result.mps.is_planar());
+
+    let alg = LocalSearchMps {
+        algorithm: Box::new(PoranenMps {}),
+        name: "PoranenLS",
+        time_limit: Some(Duration::from_secs(2)),
+    };
+    let result = alg.solve(&graph);
+    assert!(result.mps.is_planar());
+    assert!(
+        result.mps.num_of_edges() >= PoranenMps {}.maximum_planar_subgraph(&graph).num_of_edges()
+    );
+    assert_eq!(result.weight, result.mps.num_of_edges() as f64);
+}
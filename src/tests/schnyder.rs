@@ -1,6 +1,15 @@
+use crate::drawing::StraightLineDrawing;
 use crate::graphs::Graph;
+use crate::ilp::IlpOptions;
+use crate::match_merge::PoranenMps;
+use crate::mps_alg::{MpsAlgorithm, MpsStatus};
 use crate::rand_graphs::bliztstein_generation;
-use crate::schnyder::schnyder_mps;
+use crate::schnyder::{
+    draw_subgraph, ranks, schnyder_drawing_with_options, schnyder_mps,
+    schnyder_mps_with_initial_solution, schnyder_mps_with_time_limit, Orders, SchnyderMps,
+};
+use crate::weights::EdgeWeights;
+use std::time::Duration;
 
 #[test]
 fn schnyder_mps_complete_test() {
@@ -22,3 +31,119 @@ fn schnyder_mps_3_regular_test() {
         assert!(mps.num_of_edges() <= 3 * n - 6);
     }
 }
+
+#[test]
+fn schnyder_mps_time_limit_test() {
+    let graph = Graph::complete(5);
+    let solution = schnyder_mps_with_time_limit(&graph, Some(Duration::from_secs(60)));
+    assert!(!solution.time_limited);
+    assert!(solution.is_optimal());
+    assert_eq!(solution.upper_bound, 9.0);
+    assert_eq!(solution.gap, 0.0);
+
+    let graph = Graph::complete(12);
+    let solution = schnyder_mps_with_time_limit(&graph, Some(Duration::from_millis(100)));
+    assert!(solution.time_limited);
+    assert!(solution.mps.is_planar());
+    assert!(solution.weight <= solution.upper_bound);
+    assert!(solution.upper_bound <= 30.0);
+    assert!((0.0..=1.0).contains(&solution.gap));
+}
+
+#[test]
+fn schnyder_mps_initial_solution_test() {
+    let degs = vec![3; 8];
+    let graph = bliztstein_generation(&degs).unwrap();
+    let initial = PoranenMps {}.maximum_planar_subgraph(&graph);
+    let solution = schnyder_mps_with_initial_solution(&graph, &initial, None);
+    assert!(solution.is_optimal());
+    assert!(solution.mps.is_planar());
+    assert_eq!(
+        solution.mps.num_of_edges(),
+        schnyder_mps(&graph).num_of_edges()
+    );
+
+    let graph = Graph::complete(12);
+    let initial = PoranenMps {}.maximum_planar_subgraph(&graph);
+    let solution =
+        schnyder_mps_with_initial_solution(&graph, &initial, Some(Duration::from_millis(100)));
+    assert!(solution.mps.is_planar());
+    assert!(solution.mps.num_of_edges() >= initial.num_of_edges());
+}
+
+#[test]
+fn schnyder_mps_result_test() {
+    let graph = Graph::complete(6);
+    let result = SchnyderMps::default().solve(&graph);
+    assert_eq!(result.status, MpsStatus::Optimal);
+    assert_eq!(result.upper_bound, 12.0);
+    assert_eq!(result.removed_edges.len(), 3);
+    assert_eq!(result.gap(), 0.0);
+
+    let algorithm = SchnyderMps {
+        time_limit: Some(Duration::from_millis(100)),
+        warm_start: Some(Box::new(PoranenMps {})),
+    };
+    let graph = Graph::complete(12);
+    let result = algorithm.solve(&graph);
+    assert_eq!(result.status, MpsStatus::TimeLimited);
+    assert!(result.upper_bound > result.weight);
+    assert!(result.wall_time >= Duration::from_millis(100));
+}
+
+#[test]
+fn schnyder_mps_weighted_test() {
+    let graph = Graph::complete(5);
+    let mut weights = EdgeWeights::new();
+    weights.set_weight(0, 1, 10.0);
+
+    let result = SchnyderMps::default().solve_weighted(&graph, &weights);
+    assert_eq!(result.status, MpsStatus::Optimal);
+    assert_eq!(result.weight, 18.0);
+    assert_eq!(result.upper_bound, 18.0);
+    assert!(result.mps.has_edge(0, 1));
+    assert!(result.mps.is_planar());
+}
+
+#[test]
+fn schnyder_drawing_test() {
+    for n in [2, 5, 7] {
+        let graph = Graph::complete(n);
+        let drawing = schnyder_drawing_with_options(&graph, &IlpOptions::default()).unwrap();
+        let mps = &drawing.solution.mps;
+        assert_eq!(mps.num_of_edges(), if n > 2 { 3 * n - 6 } else { 1 });
+        assert_eq!(drawing.coordinates.len(), n);
+        assert!(drawing.drawing().is_plane(mps));
+
+        let rank = ranks(&drawing.orders);
+        for (u, v) in mps.all_edges() {
+            for w in (0..n).filter(|&w| w != u && w != v) {
+                assert!((0..3).any(|i| rank[u][i] < rank[w][i] && rank[v][i] < rank[w][i]));
+            }
+        }
+
+        let svg = drawing.to_svg();
+        assert_eq!(svg.matches("<line").count(), mps.num_of_edges());
+        assert_eq!(svg.matches("<circle").count(), n);
+    }
+}
+
+#[test]
+fn schnyder_drawing_fallback_test() {
+    let triangle = Graph::complete(3);
+    let orders: Orders = [vec![0, 1, 2], vec![1, 2, 0], vec![2, 0, 1]];
+
+    let (kept, coordinates, fallback) = draw_subgraph(&triangle, Some(orders.clone()));
+    assert!(!fallback);
+    assert_eq!(kept, orders);
+    assert_eq!(coordinates, vec![(1, 3), (1, 0), (4, 3)]);
+
+    let (replaced, coordinates, fallback) = draw_subgraph(&triangle, None);
+    assert!(fallback);
+    let rank = ranks(&replaced);
+    for (u, v) in triangle.all_edges() {
+        let w = 3 - u - v;
+        assert!((0..3).any(|i| rank[u][i] < rank[w][i] && rank[v][i] < rank[w][i]));
+    }
+    assert!(StraightLineDrawing::from_grid(&coordinates).is_plane(&triangle));
+}
@@ -0,0 +1,83 @@
+use crate::graphs::Graph;
+use crate::match_merge::PoranenMps;
+use crate::mps_alg::MpsAlgorithm;
+use crate::planarity::triangulate;
+use crate::rand_graphs::general_random_graph;
+use crate::schnyder_wood::{schnyder_wood, SchnyderWood};
+
+fn assert_valid_schnyder_wood(graph: &Graph) {
+    let n = graph.num_of_vertices();
+    let (triangulation, embedding) = triangulate(graph).unwrap();
+    let wood = SchnyderWood::new(&embedding).unwrap();
+    let outer = wood.outer_vertices();
+
+    // every inner vertex reaches the root of each tree along edges of the triangulation
+    for v in graph.vertices().filter(|v| !outer.contains(v)) {
+        for (tree, &root) in outer.iter().enumerate() {
+            let mut u = v;
+            for _ in 0..n {
+                match wood.parent(tree, u) {
+                    Some(parent) => {
+                        assert!(triangulation.has_edge(u, parent));
+                        u = parent;
+                    }
+                    None => break,
+                }
+            }
+            assert_eq!(u, root);
+        }
+    }
+
+    let coordinates = wood.coordinates();
+    assert!(coordinates
+        .iter()
+        .all(|c| c.iter().sum::<usize>() == 2 * n - 5));
+
+    let orders = wood.orders();
+    let mut rank = vec![vec![0; n]; 3];
+    for i in 0..3 {
+        for (r, &v) in orders[i].iter().enumerate() {
+            rank[i][v] = r;
+        }
+    }
+
+    // dimension at most three: both ends of every edge precede every other vertex in some order
+    for (u, v) in triangulation.all_edges() {
+        for w in graph.vertices().filter(|&w| w != u && w != v) {
+            assert!((0..3).any(|i| rank[i][u] < rank[i][w] && rank[i][v] < rank[i][w]));
+        }
+    }
+
+    // no pair of vertices is ordered the same way in all three orders
+    for u in graph.vertices() {
+        for v in graph.vertices().filter(|&v| v != u) {
+            assert!((0..3).any(|i| rank[i][u] > rank[i][v]));
+        }
+    }
+}
+
+#[test]
+fn complete_schnyder_wood_test() {
+    for n in 3..=4 {
+        assert_valid_schnyder_wood(&Graph::complete(n));
+    }
+
+    assert!(schnyder_wood(&Graph::complete(2)).is_none());
+    assert!(schnyder_wood(&Graph::complete(5)).is_none());
+}
+
+#[test]
+fn random_schnyder_wood_test() {
+    let poranen = PoranenMps {};
+
+    for n in [5, 10, 30] {
+        for m in [n, 2 * n, 3 * n] {
+            for _ in 0..5 {
+                let graph = poranen.maximum_planar_subgraph(
+                    &general_random_graph(n, m.min(n * (n - 1) / 2)).unwrap(),
+                );
+                assert_valid_schnyder_wood(&graph);
+            }
+        }
+    }
+}
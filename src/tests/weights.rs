@@ -0,0 +1,44 @@
+use crate::graphs::Graph;
+use crate::weights::EdgeWeights;
+
+#[test]
+fn edge_weights_test() {
+    let graph = Graph::complete(4);
+    let mut weights = EdgeWeights::new();
+    assert!(weights.is_unit());
+    assert_eq!(weights.total_weight(&graph), 6.0);
+    assert_eq!(weights.upper_bound(&graph), 6.0);
+
+    weights.set_weight(2, 1, 2.5);
+    assert_eq!(weights.weight(1, 2), 2.5);
+    assert_eq!(weights.weight(2, 1), 2.5);
+    assert_eq!(weights.weight(0, 1), 1.0);
+    assert!(!weights.is_unit());
+    assert!(!weights.is_integral());
+    assert_eq!(weights.total_weight(&graph), 7.5);
+
+    let graph = Graph::complete(5);
+    weights.set_weight(0, 3, 0.0);
+    assert_eq!(weights.upper_bound(&graph), 10.5);
+}
+
+#[test]
+#[should_panic(expected = "finite and non-negative")]
+fn negative_weight_test() {
+    EdgeWeights::new().set_weight(0, 1, -1.0);
+}
+
+#[test]
+#[should_panic(expected = "finite and non-negative")]
+fn nan_weight_test() {
+    EdgeWeights::new().set_weight(0, 1, f64::NAN);
+}
+
+#[test]
+fn empty_weight_test() {
+    let weights = EdgeWeights::new();
+    let graph = Graph::empty(3);
+    assert!(weights.sum(&[]).is_sign_positive());
+    assert!(weights.total_weight(&graph).is_sign_positive());
+    assert!(weights.upper_bound(&graph).is_sign_positive());
+}
@@ -0,0 +1,122 @@
+use crate::graphs::Graph;
+use std::collections::HashMap;
+
+/// A struct representing the weights of the edges of a graph.
+///
+/// Edges without an explicitly set weight weigh one, so the default weights turn the weighted MPS problem into the
+/// unweighted one.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EdgeWeights {
+    weights: HashMap<(usize, usize), f64>,
+}
+
+impl EdgeWeights {
+    /// Creates weights equal to one for all edges.
+    ///
+    /// # Returns
+    /// * A new instance of `EdgeWeights`.
+    pub fn new() -> Self {
+        EdgeWeights {
+            weights: HashMap::new(),
+        }
+    }
+
+    /// Sets the weight of an edge.
+    ///
+    /// # Arguments
+    /// * `u` - The index of the first vertex.
+    /// * `v` - The index of the second vertex.
+    /// * `weight` - The non-negative weight of the edge.
+    ///
+    /// # Panics
+    /// * If the weight is negative, infinite or NaN.
+    pub fn set_weight(&mut self, u: usize, v: usize, weight: f64) {
+        assert!(
+            weight.is_finite() && weight >= 0.0,
+            "the weight of edge ({u}, {v}) must be finite and non-negative, got {weight}"
+        );
+        self.weights.insert((u.min(v), u.max(v)), weight);
+    }
+
+    /// Returns the weight of an edge.
+    ///
+    /// # Arguments
+    /// * `u` - The index of the first vertex.
+    /// * `v` - The index of the second vertex.
+    ///
+    /// # Returns
+    /// * The weight of the edge, one if it was not set.
+    pub fn weight(&self, u: usize, v: usize) -> f64 {
+        self.weights
+            .get(&(u.min(v), u.max(v)))
+            .copied()
+            .unwrap_or(1.0)
+    }
+
+    /// Sums the weights of the given edges.
+    ///
+    /// # Arguments
+    /// * `edges` - A slice of edges represented as tuples of vertex indices.
+    ///
+    /// # Returns
+    /// * The sum of the weights.
+    pub fn sum(&self, edges: &[(usize, usize)]) -> f64 {
+        // summing an empty f64 iterator yields -0.0
+        edges
+            .iter()
+            .fold(0.0, |acc, &(u, v)| acc + self.weight(u, v))
+    }
+
+    /// Computes the total weight of the edges of a graph.
+    ///
+    /// # Arguments
+    /// * `g` - A reference to the graph.
+    ///
+    /// # Returns
+    /// * The sum of the weights of all edges.
+    pub fn total_weight(&self, g: &Graph) -> f64 {
+        self.sum(&g.all_edges())
+    }
+
+    /// Checks if all edges weigh one, so the weighted MPS problem is the unweighted one.
+    ///
+    /// # Returns
+    /// * `true` if no weight other than one was set, `false` otherwise.
+    pub fn is_unit(&self) -> bool {
+        self.weights.values().all(|&weight| weight == 1.0)
+    }
+
+    /// Checks if all weights are integers, so the weight of every subgraph is an integer as well.
+    ///
+    /// # Returns
+    /// * `true` if all weights are integers, `false` otherwise.
+    pub fn is_integral(&self) -> bool {
+        self.weights.values().all(|weight| weight.fract() == 0.0)
+    }
+
+    /// Computes the trivial upper bound on the weight of a maximum planar subgraph.
+    ///
+    /// A planar graph has at most `3n - 6` edges, so no planar subgraph is heavier than that many heaviest edges.
+    ///
+    /// # Arguments
+    /// * `g` - A reference to the input graph.
+    ///
+    /// # Returns
+    /// * The sum of the largest positive weights, taking at most `3n - 6` of them.
+    pub fn upper_bound(&self, g: &Graph) -> f64 {
+        let n = g.num_of_vertices();
+        let mut weights = g
+            .all_edges()
+            .into_iter()
+            .map(|(u, v)| self.weight(u, v))
+            .filter(|&weight| weight > 0.0)
+            .collect::<Vec<f64>>();
+        weights.sort_by(|a, b| b.total_cmp(a));
+
+        if n > 2 {
+            weights.truncate(3 * n - 6);
+        }
+
+        weights.iter().fold(0.0, |acc, weight| acc + weight)
+    }
+}